@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{auth::AuthSession, Result};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountType {
+    Offline,
+    Microsoft,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Account {
+    pub id: String,
+    pub account_type: AccountType,
+    pub username: String,
+    pub uuid: String,
+    pub xuid: Option<String>,
+}
+
+/// Public part of the store, saved in `accounts.json`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AccountsFile {
+    pub accounts: Vec<Account>,
+    pub selected_account_id: Option<String>,
+}
+
+/// Tokens kept apart from `accounts.json`, keyed by account id.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AccountSecretsFile {
+    pub secrets: HashMap<String, AccountSecrets>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AccountSecrets {
+    pub access_token: String,
+    pub expires_at: u64,
+    pub refresh_token: Option<String>,
+}
+
+impl Account {
+    /// Microsoft accounts need their stored tokens; without them the account
+    /// has to sign in again.
+    pub fn build_session(&self, secrets: Option<&AccountSecrets>) -> Result<AuthSession> {
+        if self.account_type == AccountType::Offline {
+            return Ok(AuthSession::offline(self.username.clone()));
+        }
+
+        let secrets = secrets.ok_or(crate::Error::AccountSessionExpired(self.id.clone()))?;
+
+        Ok(AuthSession {
+            username: self.username.clone(),
+            uuid: self.uuid.clone(),
+            access_token: secrets.access_token.clone(),
+            xuid: self.xuid.clone(),
+            user_type: "msa".to_string(),
+            expires_at: secrets.expires_at,
+            refresh_token: secrets.refresh_token.clone(),
+        })
+    }
+}
+
+impl From<&AuthSession> for AccountSecrets {
+    fn from(session: &AuthSession) -> Self {
+        Self {
+            access_token: session.access_token.clone(),
+            expires_at: session.expires_at,
+            refresh_token: session.refresh_token.clone(),
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use crate::{
+    auth::{offline_player_uuid, AuthSession, MicrosoftAuthenticator},
+    files::{load_serialized_file, save_private_serialized_file, save_serialized_file},
+    LauncherPath, LauncherPaths, Result,
+};
+
+use super::{Account, AccountSecretsFile, AccountType, AccountsFile};
+
+#[derive(Debug)]
+pub struct AccountStore {
+    launcher_paths: LauncherPaths,
+    accounts_file: AccountsFile,
+    secrets_file: AccountSecretsFile,
+}
+
+impl AccountStore {
+    pub async fn load(launcher_paths: LauncherPaths) -> Result<Self> {
+        let accounts_file =
+            load_serialized_file(launcher_paths.get_path(LauncherPath::AccountsFile))
+                .await?
+                .unwrap_or_default();
+        let secrets_file =
+            load_serialized_file(launcher_paths.get_path(LauncherPath::AccountSecretsFile))
+                .await?
+                .unwrap_or_default();
+
+        Ok(Self {
+            launcher_paths,
+            accounts_file,
+            secrets_file,
+        })
+    }
+
+    pub async fn persist(&self) -> Result<()> {
+        save_serialized_file(
+            self.launcher_paths.get_path(LauncherPath::AccountsFile),
+            &self.accounts_file,
+        )
+        .await?;
+        save_private_serialized_file(
+            self.launcher_paths
+                .get_path(LauncherPath::AccountSecretsFile),
+            &self.secrets_file,
+        )
+        .await
+    }
+
+    pub fn accounts(&self) -> &[Account] {
+        &self.accounts_file.accounts
+    }
+
+    pub fn selected_account(&self) -> Option<&Account> {
+        let selected_account_id = self.accounts_file.selected_account_id.as_ref()?;
+
+        self.find_account(selected_account_id)
+    }
+
+    pub fn find_account(&self, account_id: &str) -> Option<&Account> {
+        self.accounts_file
+            .accounts
+            .iter()
+            .find(|account| account.id == account_id)
+    }
+
+    pub fn add_offline_account(&mut self, username: String) -> Account {
+        let account = Account {
+            id: uuid::Uuid::new_v4().simple().to_string(),
+            account_type: AccountType::Offline,
+            uuid: offline_player_uuid(&username).simple().to_string(),
+            username,
+            xuid: None,
+        };
+
+        self.accounts_file.accounts.push(account.clone());
+        account
+    }
+
+    /// Adds the account behind a Microsoft session, or updates it when an
+    /// account with the same profile UUID is already stored.
+    pub fn add_microsoft_account(&mut self, session: &AuthSession) -> Account {
+        let existing_account = self.accounts_file.accounts.iter_mut().find(|account| {
+            account.account_type == AccountType::Microsoft && account.uuid == session.uuid
+        });
+
+        let account = match existing_account {
+            Some(account) => {
+                account.username = session.username.clone();
+                account.xuid = session.xuid.clone();
+                account.clone()
+            }
+            None => {
+                let account = Account {
+                    id: uuid::Uuid::new_v4().simple().to_string(),
+                    account_type: AccountType::Microsoft,
+                    username: session.username.clone(),
+                    uuid: session.uuid.clone(),
+                    xuid: session.xuid.clone(),
+                };
+                self.accounts_file.accounts.push(account.clone());
+                account
+            }
+        };
+
+        self.secrets_file
+            .secrets
+            .insert(account.id.clone(), session.into());
+
+        account
+    }
+
+    pub fn remove_account(&mut self, account_id: &str) -> Result<Account> {
+        let index = self
+            .accounts_file
+            .accounts
+            .iter()
+            .position(|account| account.id == account_id)
+            .ok_or(crate::Error::AccountNotFound(account_id.to_string()))?;
+
+        if self.accounts_file.selected_account_id.as_deref() == Some(account_id) {
+            self.accounts_file.selected_account_id = None;
+        }
+        self.secrets_file.secrets.remove(account_id);
+
+        Ok(self.accounts_file.accounts.remove(index))
+    }
+
+    pub fn select_account(&mut self, account_id: &str) -> Result<Account> {
+        let account = self
+            .find_account(account_id)
+            .cloned()
+            .ok_or(crate::Error::AccountNotFound(account_id.to_string()))?;
+
+        self.accounts_file.selected_account_id = Some(account.id.clone());
+
+        Ok(account)
+    }
+
+    /// Builds the session of the selected account. Expired Microsoft sessions
+    /// are refreshed with the stored refresh token and persisted again.
+    pub async fn selected_session(
+        &mut self,
+        authenticator: Option<&MicrosoftAuthenticator>,
+    ) -> Result<Option<AuthSession>> {
+        let account = match self.selected_account() {
+            Some(account) => account.clone(),
+            None => return Ok(None),
+        };
+
+        let session = account.build_session(self.secrets_file.secrets.get(&account.id))?;
+        if account.account_type == AccountType::Offline || !session.is_expired() {
+            return Ok(Some(session));
+        }
+
+        let refresh_token = session
+            .refresh_token
+            .as_ref()
+            .ok_or(crate::Error::AccountSessionExpired(account.id.clone()))?;
+        let authenticator =
+            authenticator.ok_or(crate::Error::AccountSessionExpired(account.id.clone()))?;
+
+        let refreshed_session = authenticator.refresh_session(refresh_token).await?;
+        self.add_microsoft_account(&refreshed_session);
+        self.persist().await?;
+
+        Ok(Some(refreshed_session))
+    }
+}
@@ -0,0 +1,5 @@
+mod account;
+mod account_store;
+
+pub use account::*;
+pub use account_store::*;
@@ -0,0 +1,47 @@
+const DEVICE_CODE_URL: &str = "https://login.microsoftonline.com/consumers/oauth2/v2.0/devicecode";
+const TOKEN_URL: &str = "https://login.microsoftonline.com/consumers/oauth2/v2.0/token";
+const XBOX_LIVE_AUTH_URL: &str = "https://user.auth.xboxlive.com/user/authenticate";
+const XSTS_AUTH_URL: &str = "https://xsts.auth.xboxlive.com/xsts/authorize";
+const MINECRAFT_LOGIN_URL: &str =
+    "https://api.minecraftservices.com/authentication/login_with_xbox";
+const MINECRAFT_PROFILE_URL: &str = "https://api.minecraftservices.com/minecraft/profile";
+
+#[derive(Clone, Debug)]
+pub struct AuthEndpoints {
+    pub device_code_url: String,
+    pub token_url: String,
+    pub xbox_live_auth_url: String,
+    pub xsts_auth_url: String,
+    pub minecraft_login_url: String,
+    pub minecraft_profile_url: String,
+}
+
+impl Default for AuthEndpoints {
+    fn default() -> Self {
+        Self {
+            device_code_url: DEVICE_CODE_URL.to_string(),
+            token_url: TOKEN_URL.to_string(),
+            xbox_live_auth_url: XBOX_LIVE_AUTH_URL.to_string(),
+            xsts_auth_url: XSTS_AUTH_URL.to_string(),
+            minecraft_login_url: MINECRAFT_LOGIN_URL.to_string(),
+            minecraft_profile_url: MINECRAFT_PROFILE_URL.to_string(),
+        }
+    }
+}
+
+impl AuthEndpoints {
+    /// Points every endpoint at the same host, keeping the real paths.
+    /// Useful to run the whole chain against a local mock server.
+    pub fn with_base_url(base_url: &str) -> Self {
+        let base_url = base_url.trim_end_matches('/');
+
+        Self {
+            device_code_url: format!("{}/consumers/oauth2/v2.0/devicecode", base_url),
+            token_url: format!("{}/consumers/oauth2/v2.0/token", base_url),
+            xbox_live_auth_url: format!("{}/user/authenticate", base_url),
+            xsts_auth_url: format!("{}/xsts/authorize", base_url),
+            minecraft_login_url: format!("{}/authentication/login_with_xbox", base_url),
+            minecraft_profile_url: format!("{}/minecraft/profile", base_url),
+        }
+    }
+}
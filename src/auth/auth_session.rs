@@ -0,0 +1,72 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+use uuid::{Builder, Uuid};
+
+const EXPIRATION_MARGIN_SECONDS: u64 = 60;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AuthSession {
+    pub username: String,
+    pub uuid: String,
+    pub access_token: String,
+    pub xuid: Option<String>,
+    pub user_type: String,
+    pub expires_at: u64,
+    pub refresh_token: Option<String>,
+}
+
+impl AuthSession {
+    pub fn offline(username: String) -> Self {
+        Self {
+            uuid: offline_player_uuid(&username).simple().to_string(),
+            username,
+            access_token: "token".to_string(),
+            xuid: None,
+            user_type: "legacy".to_string(),
+            expires_at: u64::MAX,
+            refresh_token: None,
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        current_timestamp().saturating_add(EXPIRATION_MARGIN_SECONDS) >= self.expires_at
+    }
+}
+
+pub fn current_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Same as Java's `UUID.nameUUIDFromBytes("OfflinePlayer:<name>")`, which is
+/// what vanilla and Bukkit servers compute for players in offline mode.
+pub fn offline_player_uuid(username: &str) -> Uuid {
+    let digest = Md5::digest(format!("OfflinePlayer:{}", username).as_bytes());
+
+    Builder::from_md5_bytes(digest.into()).into_uuid()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offline_uuid_matches_java_name_uuid() {
+        let uuid = offline_player_uuid("Notch");
+
+        assert_eq!(uuid.to_string(), "b50ad385-829d-3141-a216-7e7d7539ba7f");
+        assert_eq!(uuid.get_version_num(), 3);
+    }
+
+    #[test]
+    fn offline_session_uses_the_simple_uuid() {
+        let session = AuthSession::offline("Notch".to_string());
+
+        assert_eq!(session.uuid, "b50ad385829d3141a2167e7d7539ba7f");
+        assert!(!session.is_expired());
+    }
+}
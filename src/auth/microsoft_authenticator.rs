@@ -0,0 +1,225 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+
+use crate::{
+    model::microsoft::{
+        MicrosoftDeviceCode, MicrosoftToken, MicrosoftTokenResponse, MinecraftLoginRequest,
+        MinecraftLoginResponse, MinecraftProfile, XboxLiveAuthError, XboxLiveAuthRequest,
+        XboxLiveAuthResponse,
+    },
+    Result,
+};
+
+use super::{current_timestamp, AuthEndpoints, AuthSession};
+
+const MICROSOFT_SCOPE: &str = "XboxLive.signin offline_access";
+const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+const SLOW_DOWN_INTERVAL_INCREMENT: u64 = 5;
+
+pub struct MicrosoftAuthenticator {
+    client: reqwest::Client,
+    client_id: String,
+    endpoints: AuthEndpoints,
+}
+
+impl MicrosoftAuthenticator {
+    pub fn new(client_id: String, endpoints: AuthEndpoints) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            client_id,
+            endpoints,
+        }
+    }
+
+    /// First step of the flow. The returned code must be shown to the user,
+    /// who then signs in at `verification_uri`.
+    pub async fn request_device_code(&self) -> Result<MicrosoftDeviceCode> {
+        let response = self
+            .client
+            .post(&self.endpoints.device_code_url)
+            .form(&[
+                ("client_id", self.client_id.as_str()),
+                ("scope", MICROSOFT_SCOPE),
+            ])
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(response.json().await?)
+    }
+
+    pub async fn login_with_device_code(
+        &self,
+        device_code: &MicrosoftDeviceCode,
+    ) -> Result<AuthSession> {
+        let microsoft_token = self.wait_for_device_authorization(device_code).await?;
+
+        self.login_with_microsoft_token(&microsoft_token).await
+    }
+
+    /// Polls the token endpoint until the user finishes signing in, the code
+    /// expires or the request is declined.
+    pub async fn wait_for_device_authorization(
+        &self,
+        device_code: &MicrosoftDeviceCode,
+    ) -> Result<MicrosoftToken> {
+        let deadline = current_timestamp() + device_code.expires_in;
+        let mut interval = device_code.interval.max(1);
+
+        loop {
+            tokio::time::sleep(Duration::from_secs(interval)).await;
+
+            let response: MicrosoftTokenResponse = self
+                .client
+                .post(&self.endpoints.token_url)
+                .form(&[
+                    ("grant_type", DEVICE_CODE_GRANT_TYPE),
+                    ("client_id", self.client_id.as_str()),
+                    ("device_code", device_code.device_code.as_str()),
+                ])
+                .send()
+                .await?
+                .json()
+                .await?;
+
+            match response {
+                MicrosoftTokenResponse::Token(token) => return Ok(token),
+                MicrosoftTokenResponse::Error(error) => match error.error.as_str() {
+                    "authorization_pending" => {}
+                    "slow_down" => interval += SLOW_DOWN_INTERVAL_INCREMENT,
+                    _ => return Err(crate::Error::MicrosoftAuthError(error.error)),
+                },
+            }
+
+            if current_timestamp() >= deadline {
+                return Err(crate::Error::MicrosoftAuthError(
+                    "expired_token".to_string(),
+                ));
+            }
+        }
+    }
+
+    pub async fn refresh_session(&self, refresh_token: &str) -> Result<AuthSession> {
+        let microsoft_token = self.refresh_microsoft_token(refresh_token).await?;
+
+        let mut session = self.login_with_microsoft_token(&microsoft_token).await?;
+        session
+            .refresh_token
+            .get_or_insert_with(|| refresh_token.to_string());
+
+        Ok(session)
+    }
+
+    pub async fn refresh_microsoft_token(&self, refresh_token: &str) -> Result<MicrosoftToken> {
+        let response: MicrosoftTokenResponse = self
+            .client
+            .post(&self.endpoints.token_url)
+            .form(&[
+                ("grant_type", "refresh_token"),
+                ("client_id", self.client_id.as_str()),
+                ("refresh_token", refresh_token),
+                ("scope", MICROSOFT_SCOPE),
+            ])
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        match response {
+            MicrosoftTokenResponse::Token(token) => Ok(token),
+            MicrosoftTokenResponse::Error(error) => {
+                Err(crate::Error::MicrosoftAuthError(error.error))
+            }
+        }
+    }
+
+    /// Exchanges a Microsoft access token for a Minecraft session, going
+    /// through Xbox Live, XSTS, Minecraft services and the profile endpoint.
+    pub async fn login_with_microsoft_token(
+        &self,
+        microsoft_token: &MicrosoftToken,
+    ) -> Result<AuthSession> {
+        let xbox_live_response = self
+            .authenticate_xbox_live(
+                &self.endpoints.xbox_live_auth_url,
+                XboxLiveAuthRequest::user(&microsoft_token.access_token),
+            )
+            .await?;
+        let xsts_response = self
+            .authenticate_xbox_live(
+                &self.endpoints.xsts_auth_url,
+                XboxLiveAuthRequest::xsts(&xbox_live_response.token),
+            )
+            .await?;
+
+        let user_info = xsts_response
+            .user_hash()
+            .ok_or(crate::Error::MicrosoftAuthError(
+                "missing xbox user hash".to_string(),
+            ))?;
+
+        let minecraft_login = self
+            .login_minecraft_services(&user_info.uhs, &xsts_response.token)
+            .await?;
+        let profile = self
+            .fetch_minecraft_profile(&minecraft_login.access_token)
+            .await?;
+
+        Ok(AuthSession {
+            username: profile.name,
+            uuid: profile.id,
+            access_token: minecraft_login.access_token,
+            xuid: user_info.xid.clone(),
+            user_type: "msa".to_string(),
+            expires_at: current_timestamp() + minecraft_login.expires_in,
+            refresh_token: microsoft_token.refresh_token.clone(),
+        })
+    }
+
+    async fn authenticate_xbox_live(
+        &self,
+        url: &str,
+        request: XboxLiveAuthRequest,
+    ) -> Result<XboxLiveAuthResponse> {
+        let response = self.client.post(url).json(&request).send().await?;
+
+        if response.status() == StatusCode::UNAUTHORIZED {
+            let error: XboxLiveAuthError = response.json().await?;
+            return Err(crate::Error::XboxLiveAuthError(error.x_err));
+        }
+
+        Ok(response.error_for_status()?.json().await?)
+    }
+
+    async fn login_minecraft_services(
+        &self,
+        user_hash: &str,
+        xsts_token: &str,
+    ) -> Result<MinecraftLoginResponse> {
+        let response = self
+            .client
+            .post(&self.endpoints.minecraft_login_url)
+            .json(&MinecraftLoginRequest::new(user_hash, xsts_token))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(response.json().await?)
+    }
+
+    async fn fetch_minecraft_profile(&self, access_token: &str) -> Result<MinecraftProfile> {
+        let response = self
+            .client
+            .get(&self.endpoints.minecraft_profile_url)
+            .bearer_auth(access_token)
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(crate::Error::MinecraftProfileNotFound);
+        }
+
+        Ok(response.error_for_status()?.json().await?)
+    }
+}
@@ -0,0 +1,7 @@
+mod auth_endpoints;
+mod auth_session;
+mod microsoft_authenticator;
+
+pub use auth_endpoints::*;
+pub use auth_session::*;
+pub use microsoft_authenticator::*;
@@ -0,0 +1,7 @@
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Sends to a frontend channel. A dropped receiver only means nobody is
+/// listening anymore, so it is not an error.
+pub(crate) fn send_to_listener<T>(sender: &UnboundedSender<T>, message: T) {
+    let _ = sender.send(message);
+}
@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    download_plan::DEFAULT_DOWNLOAD_PARALLELISM,
     files::{load_serialized_file, save_serialized_file},
+    jvm_settings::JvmSettings,
+    launch_hooks::LaunchHooks,
     launcher_paths::{LauncherPath, LauncherPaths},
     Result,
 };
@@ -10,6 +13,18 @@ use crate::{
 pub struct LauncherConfig {
     pub last_selected_version_id: Option<String>,
     pub user_name: Option<String>,
+    /// Identifies this installation in `${clientid}`; unrelated to the
+    /// Microsoft OAuth client id.
+    #[serde(alias = "client_id")]
+    pub launcher_client_id: Option<String>,
+    /// How many files are downloaded at the same time.
+    pub download_parallelism: Option<usize>,
+    /// Defaults for every instance, see `JvmSettings::merged_with`.
+    #[serde(default)]
+    pub jvm_settings: JvmSettings,
+    /// Defaults for every instance, see `LaunchHooks::merged_with`.
+    #[serde(default)]
+    pub launch_hooks: LaunchHooks,
 }
 
 impl Default for LauncherConfig {
@@ -17,6 +32,10 @@ impl Default for LauncherConfig {
         Self {
             last_selected_version_id: Default::default(),
             user_name: Some("Player".to_string()),
+            launcher_client_id: Default::default(),
+            download_parallelism: Default::default(),
+            jvm_settings: Default::default(),
+            launch_hooks: Default::default(),
         }
     }
 }
@@ -30,6 +49,19 @@ impl LauncherConfig {
         Ok(config)
     }
 
+    /// Identifies this launcher installation in `${clientid}`, generated on
+    /// first use.
+    pub fn launcher_client_id(&mut self) -> String {
+        self.launcher_client_id
+            .get_or_insert_with(|| uuid::Uuid::new_v4().simple().to_string())
+            .clone()
+    }
+
+    pub fn download_parallelism(&self) -> usize {
+        self.download_parallelism
+            .unwrap_or(DEFAULT_DOWNLOAD_PARALLELISM)
+    }
+
     pub async fn persist(&self, launcher_paths: LauncherPaths) -> Result<()> {
         let path = launcher_paths.get_path(LauncherPath::LauncherConfig);
 
@@ -0,0 +1,184 @@
+use std::collections::HashSet;
+
+use futures::StreamExt;
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    download_progress::{DownloadEvent, DownloadProgress},
+    files::{download_file, is_file_valid},
+    http_client::{check_download_failures, DownloadFailure, TransferEvent},
+    Result,
+};
+
+pub const DEFAULT_DOWNLOAD_PARALLELISM: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DownloadKind {
+    Client,
+    Library,
+    Native,
+    AssetObject,
+    Runtime,
+    LoggingConfig,
+}
+
+/// How a batch of downloads runs, who hears about it and how it is stopped.
+#[derive(Clone, Copy)]
+pub struct DownloadContext<'a> {
+    pub parallelism: usize,
+    pub progress: &'a dyn DownloadProgress,
+    pub cancellation: &'a CancellationToken,
+}
+
+#[derive(Debug, Clone)]
+pub struct DownloadTask {
+    pub kind: DownloadKind,
+    pub path: String,
+    pub url: String,
+    pub sha1: Option<String>,
+    pub size: Option<u64>,
+}
+
+/// Every file a version needs, collected up front so they can all go
+/// through one bounded queue instead of stage after stage.
+#[derive(Debug, Default)]
+pub struct DownloadPlan {
+    pub tasks: Vec<DownloadTask>,
+    planned_paths: HashSet<String>,
+}
+
+impl DownloadContext<'_> {
+    pub fn check_cancelled(&self) -> Result<()> {
+        if self.cancellation.is_cancelled() {
+            return Err(crate::Error::Cancelled);
+        }
+
+        Ok(())
+    }
+}
+
+impl DownloadTask {
+    async fn download(
+        &self,
+        progress: &dyn DownloadProgress,
+    ) -> std::result::Result<(), DownloadFailure> {
+        match self.download_if_invalid(progress).await {
+            Ok(cached) => {
+                progress.on_event(DownloadEvent::FileFinished {
+                    kind: self.kind,
+                    path: self.path.clone(),
+                    size: self.size,
+                    cached,
+                });
+                Ok(())
+            }
+            Err(error) => {
+                progress.on_event(DownloadEvent::FileFailed {
+                    kind: self.kind,
+                    path: self.path.clone(),
+                    error: format!("{:?}", error),
+                });
+                Err(DownloadFailure {
+                    path: self.path.clone(),
+                    url: self.url.clone(),
+                    error,
+                })
+            }
+        }
+    }
+
+    /// Returns whether the file on disk was already valid.
+    async fn download_if_invalid(&self, progress: &dyn DownloadProgress) -> Result<bool> {
+        if is_file_valid(&self.path, self.sha1.as_deref(), self.size).await? {
+            return Ok(true);
+        }
+
+        progress.on_event(DownloadEvent::FileStarted {
+            kind: self.kind,
+            path: self.path.clone(),
+            size: self.size,
+        });
+
+        download_file(
+            self.path.clone(),
+            self.url.clone(),
+            self.sha1.as_deref(),
+            self.size,
+            &|event| {
+                progress.on_event(match event {
+                    TransferEvent::Progress(downloaded) => DownloadEvent::FileProgress {
+                        path: self.path.clone(),
+                        downloaded,
+                    },
+                    TransferEvent::Retrying {
+                        retry,
+                        delay,
+                        error,
+                    } => DownloadEvent::FileRetrying {
+                        path: self.path.clone(),
+                        retry,
+                        delay,
+                        error,
+                    },
+                })
+            },
+        )
+        .await?;
+
+        Ok(false)
+    }
+}
+
+impl DownloadPlan {
+    /// Files planned twice, such as asset objects sharing a hash, are only
+    /// downloaded once.
+    pub fn add(&mut self, task: DownloadTask) {
+        if self.planned_paths.insert(task.path.clone()) {
+            self.tasks.push(task);
+        }
+    }
+
+    pub fn total_size(&self) -> u64 {
+        self.tasks.iter().filter_map(|task| task.size).sum()
+    }
+
+    /// Downloads up to `parallelism` files at a time. A failed file does not
+    /// stop the others; all failures are reported together at the end.
+    ///
+    /// On cancellation the downloads in flight are dropped and their `.part`
+    /// files removed, while files that were already completed stay in place.
+    pub async fn execute(&self, context: DownloadContext<'_>) -> Result<()> {
+        context.check_cancelled()?;
+        context.progress.on_event(DownloadEvent::Planned {
+            files: self.tasks.len(),
+            bytes: self.total_size(),
+        });
+
+        let downloads = futures::stream::iter(&self.tasks)
+            .map(|task| task.download(context.progress))
+            .buffer_unordered(context.parallelism.max(1))
+            .filter_map(|result| async move { result.err() })
+            .collect::<Vec<_>>();
+
+        let failures = tokio::select! {
+            failures = downloads => failures,
+            _ = context.cancellation.cancelled() => {
+                self.remove_part_files().await?;
+                return Err(crate::Error::Cancelled);
+            }
+        };
+
+        check_download_failures(failures)
+    }
+
+    async fn remove_part_files(&self) -> Result<()> {
+        for task in &self.tasks {
+            match tokio::fs::remove_file(format!("{}.part", task.path)).await {
+                Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
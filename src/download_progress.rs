@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+use crate::{channel::send_to_listener, download_plan::DownloadKind};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DownloadStage {
+    /// Version JSON, asset index and runtime manifest, which decide what
+    /// else has to be downloaded.
+    Metadata,
+    Files,
+    LegacyAssets,
+    LoggingConfig,
+    Natives,
+    /// The processors of a Forge or NeoForge installer, which patch the
+    /// client jar.
+    Processors,
+}
+
+#[derive(Debug, Clone)]
+pub enum DownloadEvent {
+    StageStarted(DownloadStage),
+    /// Totals of the plan, from the sizes declared by the manifests.
+    Planned {
+        files: usize,
+        bytes: u64,
+    },
+    FileStarted {
+        kind: DownloadKind,
+        path: String,
+        size: Option<u64>,
+    },
+    /// Bytes of the file downloaded so far.
+    FileProgress {
+        path: String,
+        downloaded: u64,
+    },
+    /// A transient failure of the file, retried after `delay`.
+    FileRetrying {
+        path: String,
+        retry: u32,
+        delay: Duration,
+        error: String,
+    },
+    /// `cached` files were already on disk and valid.
+    FileFinished {
+        kind: DownloadKind,
+        path: String,
+        size: Option<u64>,
+        cached: bool,
+    },
+    FileFailed {
+        kind: DownloadKind,
+        path: String,
+        error: String,
+    },
+    /// An installer processor started, `index` counting from zero.
+    ProcessorStarted {
+        jar: String,
+        index: usize,
+        total: usize,
+    },
+    Finished,
+}
+
+/// Receives progress events while a version is downloaded. Files are
+/// downloaded concurrently, so events of different files interleave.
+pub trait DownloadProgress: Send + Sync {
+    fn on_event(&self, event: DownloadEvent);
+}
+
+#[derive(Debug, Default)]
+pub struct NoDownloadProgress;
+
+impl DownloadProgress for NoDownloadProgress {
+    fn on_event(&self, _event: DownloadEvent) {}
+}
+
+/// Forwards events to a channel, for frontends that draw progress on their
+/// own task.
+pub struct ChannelDownloadProgress {
+    sender: UnboundedSender<DownloadEvent>,
+}
+
+impl ChannelDownloadProgress {
+    pub fn new() -> (Self, UnboundedReceiver<DownloadEvent>) {
+        let (sender, receiver) = unbounded_channel();
+        (Self { sender }, receiver)
+    }
+}
+
+impl DownloadProgress for ChannelDownloadProgress {
+    fn on_event(&self, event: DownloadEvent) {
+        send_to_listener(&self.sender, event);
+    }
+}
@@ -1,5 +1,8 @@
+use zip::result::ZipError;
 use zip_extract::ZipExtractError;
 
+use crate::http_client::DownloadFailure;
+
 #[derive(Debug)]
 pub enum Error {
     RequestError(reqwest::Error),
@@ -7,6 +10,8 @@ pub enum Error {
     SerializationError(serde_json::Error),
     NoVersionManifestError,
     VersionNotFound(String),
+    /// A version that, through `inheritsFrom`, ends up inheriting from itself.
+    VersionInheritanceCycle(String),
     VersionNotSelectedError,
     LibraryDownloadNotFound(String),
     NativeLibraryExtractError(ZipExtractError),
@@ -14,6 +19,37 @@ pub enum Error {
     RuntimeNotFound(String),
     TokioError(tokio::task::JoinError),
     UnsupportedPlatform(String),
+    FileHashMismatch {
+        path: String,
+        expected: String,
+        actual: String,
+    },
+    MicrosoftAuthError(String),
+    XboxLiveAuthError(u64),
+    MinecraftProfileNotFound,
+    AccountNotFound(String),
+    AccountSessionExpired(String),
+    LoaderVersionNotFound(String),
+    ZipError(ZipError),
+    UnsupportedInstaller(String),
+    InstallerProcessorFailed(String),
+    UnknownLaunchPlaceholder(String),
+    HttpStatusError {
+        url: String,
+        status: u16,
+        /// Seconds the server asked to wait before retrying.
+        retry_after: Option<u64>,
+    },
+    DownloadsFailed(Vec<DownloadFailure>),
+    Cancelled,
+    InstanceNotFound(String),
+    InvalidJvmSettings(String),
+    InvalidInstanceImport(String),
+    HookFailed {
+        command: String,
+        /// `None` when the hook was killed by a signal.
+        exit_code: Option<i32>,
+    },
 }
 
 impl From<reqwest::Error> for Error {
@@ -45,3 +81,9 @@ impl From<tokio::task::JoinError> for Error {
         Error::TokioError(err)
     }
 }
+
+impl From<ZipError> for Error {
+    fn from(err: ZipError) -> Self {
+        Error::ZipError(err)
+    }
+}
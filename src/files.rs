@@ -1,34 +1,185 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use serde::{de::DeserializeOwned, Serialize};
+use sha1::{Digest, Sha1};
+use tokio::io::AsyncReadExt;
 
-use crate::{http_client, Result};
+use crate::{
+    http_client::{self, TransferEvent},
+    Result,
+};
 
-pub async fn load_file_or_download_serialized<T>(path: String, download_url: String) -> Result<T>
+pub async fn load_file_or_download_serialized<T>(
+    path: String,
+    download_url: String,
+    sha1: Option<&str>,
+    size: Option<u64>,
+) -> Result<T>
 where
     T: Serialize + DeserializeOwned,
 {
-    if let Some(file_content) = load_serialized_file(path.clone()).await? {
-        Ok(file_content)
-    } else {
-        let downloaded_content = http_client::download(download_url.clone()).await?;
-        save_file(path.clone(), downloaded_content.clone()).await?;
-
-        let parsed_content = serde_json::from_slice(&downloaded_content)?;
-        Ok(parsed_content)
+    ensure_file_downloaded(path.clone(), download_url, sha1, size).await?;
+    load_serialized_file(path)
+        .await?
+        .ok_or(crate::Error::IoError(std::io::ErrorKind::NotFound.into()))
+}
+
+pub async fn load_file_or_download(
+    path: String,
+    download_url: String,
+    sha1: Option<&str>,
+    size: Option<u64>,
+) -> Result<Vec<u8>> {
+    ensure_file_downloaded(path.clone(), download_url, sha1, size).await?;
+
+    Ok(tokio::fs::read(path).await?)
+}
+
+/// Same as `load_file_or_download`, for callers that only need the file on
+/// disk and not its content in memory.
+pub async fn ensure_file_downloaded(
+    path: String,
+    download_url: String,
+    sha1: Option<&str>,
+    size: Option<u64>,
+) -> Result<()> {
+    if is_file_valid(&path, sha1, size).await? {
+        return Ok(());
+    }
+
+    download_file(path, download_url, sha1, size, &|_| {}).await
+}
+
+/// Downloads into `<path>.part` and only renames it into place once it
+/// matches the expected hash and size, so a killed download never leaves a
+/// truncated file at `path`. A `.part` left by an earlier run is resumed
+/// when the result can be verified; if it does not match, the retry starts
+/// from scratch. Without a hash or size, stale bytes joined with the new
+/// content could not be told apart, so the download always starts over.
+/// `on_event` hears about the bytes written so far and about retries.
+pub async fn download_file(
+    path: String,
+    download_url: String,
+    sha1: Option<&str>,
+    size: Option<u64>,
+    on_event: &(dyn Fn(TransferEvent) + Sync),
+) -> Result<()> {
+    let part_path = format!("{}.part", path);
+
+    let resume = sha1.is_some() || size.is_some();
+
+    let mut downloaded =
+        http_client::download_to_file(download_url.clone(), &part_path, resume, on_event).await?;
+    if resume && !is_hash_valid(&downloaded.sha1, downloaded.size, sha1, size) {
+        downloaded =
+            http_client::download_to_file(download_url, &part_path, false, on_event).await?;
+    }
+
+    if !is_hash_valid(&downloaded.sha1, downloaded.size, sha1, size) {
+        tokio::fs::remove_file(&part_path).await?;
+        return Err(hash_mismatch_error(
+            path,
+            &downloaded.sha1,
+            downloaded.size,
+            sha1,
+            size,
+        ));
+    }
+
+    tokio::fs::rename(part_path, path).await?;
+    Ok(())
+}
+
+/// Checks an existing file without loading it in memory; the size is
+/// compared first since it is free.
+pub async fn is_file_valid(path: &str, sha1: Option<&str>, size: Option<u64>) -> Result<bool> {
+    let metadata = match tokio::fs::metadata(path).await {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(err) => return Err(err.into()),
+    };
+
+    if size.is_some_and(|size| metadata.len() != size) {
+        return Ok(false);
+    }
+
+    match sha1 {
+        Some(sha1) => Ok(compute_file_sha1(path).await?.eq_ignore_ascii_case(sha1)),
+        None => Ok(true),
     }
 }
 
-pub async fn load_file_or_download(path: String, download_url: String) -> Result<Vec<u8>> {
-    if let Some(file_content) = load_file(path.clone()).await? {
-        Ok(file_content)
-    } else {
-        let downloaded_content = http_client::download(download_url).await?;
-        save_file(path, downloaded_content.clone()).await?;
-        Ok(downloaded_content)
+/// Checks the content against whatever the manifest declares; a missing
+/// hash or size is not checked.
+pub fn is_content_valid(content: &[u8], sha1: Option<&str>, size: Option<u64>) -> bool {
+    is_hash_valid(&compute_sha1(content), content.len() as u64, sha1, size)
+}
+
+fn is_hash_valid(
+    actual_sha1: &str,
+    actual_size: u64,
+    sha1: Option<&str>,
+    size: Option<u64>,
+) -> bool {
+    let is_size_valid = size.is_none_or(|size| actual_size == size);
+    let is_sha1_valid = sha1.is_none_or(|sha1| actual_sha1.eq_ignore_ascii_case(sha1));
+
+    is_size_valid && is_sha1_valid
+}
+
+/// Reports the hash when it is known and differs, the size otherwise.
+fn hash_mismatch_error(
+    path: String,
+    actual_sha1: &str,
+    actual_size: u64,
+    sha1: Option<&str>,
+    size: Option<u64>,
+) -> crate::Error {
+    match (sha1, size) {
+        (Some(sha1), _) if !actual_sha1.eq_ignore_ascii_case(sha1) => {
+            crate::Error::FileHashMismatch {
+                path,
+                expected: sha1.to_string(),
+                actual: actual_sha1.to_string(),
+            }
+        }
+        _ => crate::Error::FileHashMismatch {
+            path,
+            expected: format!("{} bytes", size.unwrap_or_default()),
+            actual: format!("{} bytes", actual_size),
+        },
     }
 }
 
+pub async fn compute_file_sha1(path: &str) -> Result<String> {
+    let mut hasher = Sha1::new();
+    update_sha1_from_file(&mut hasher, path).await?;
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Feeds the whole file to `hasher`, returning how many bytes were read.
+pub async fn update_sha1_from_file(hasher: &mut Sha1, path: &str) -> Result<u64> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut buffer = vec![0; 64 * 1024];
+    let mut size = 0;
+
+    loop {
+        let read = file.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        size += read as u64;
+    }
+
+    Ok(size)
+}
+
+pub fn compute_sha1(content: &[u8]) -> String {
+    format!("{:x}", Sha1::digest(content))
+}
+
 pub async fn load_serialized_file<T>(path: String) -> Result<Option<T>>
 where
     T: Serialize + DeserializeOwned,
@@ -57,14 +208,68 @@ pub async fn save_serialized_file(path: String, content: impl Serialize) -> Resu
     save_file(path, serialized_content.into_bytes()).await
 }
 
+/// Same as `save_serialized_file`, but the file is only readable by its owner.
+pub async fn save_private_serialized_file(path: String, content: impl Serialize) -> Result<()> {
+    create_parent_folders(path.clone()).await?;
+    let serialized_content = serde_json::to_string(&content)?;
+    save_private_file(path, serialized_content.into_bytes()).await
+}
+
 pub async fn save_file(path: String, content: Vec<u8>) -> Result<()> {
     create_parent_folders(path.clone()).await?;
     tokio::fs::write(path, content).await?;
     Ok(())
 }
 
+#[cfg(unix)]
+pub async fn save_private_file(path: String, content: Vec<u8>) -> Result<()> {
+    use std::{fs::Permissions, os::unix::fs::PermissionsExt};
+    use tokio::io::AsyncWriteExt;
+
+    create_parent_folders(path.clone()).await?;
+    let mut file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(&path)
+        .await?;
+    tokio::fs::set_permissions(&path, Permissions::from_mode(0o600)).await?;
+    file.write_all(&content).await?;
+    file.flush().await?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub async fn save_private_file(path: String, content: Vec<u8>) -> Result<()> {
+    save_file(path, content).await
+}
+
 pub async fn create_parent_folders(path: String) -> Result<()> {
     let parent = Path::new(&path).parent().unwrap();
     tokio::fs::create_dir_all(parent).await?;
     Ok(())
 }
+
+/// Copies the contents of `source` into `target`, creating missing folders.
+/// Existing files are only replaced when `overwrite` is set.
+pub async fn copy_dir_all(source: &str, target: &str, overwrite: bool) -> Result<()> {
+    let mut pending = vec![(PathBuf::from(source), PathBuf::from(target))];
+
+    while let Some((source_dir, target_dir)) = pending.pop() {
+        tokio::fs::create_dir_all(&target_dir).await?;
+
+        let mut entries = tokio::fs::read_dir(&source_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let target_path = target_dir.join(entry.file_name());
+
+            if entry.file_type().await?.is_dir() {
+                pending.push((entry.path(), target_path));
+            } else if overwrite || !target_path.exists() {
+                tokio::fs::copy(entry.path(), target_path).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,312 @@
+use std::{path::Path, process::ExitStatus, time::SystemTime};
+
+use crate::{launcher_paths::join_paths, Result};
+
+const CRASH_REPORTS_DIR: &str = "crash-reports";
+const JVM_CRASH_LOG_PREFIX: &str = "hs_err_pid";
+
+/// Markers found in crash reports, JVM crash logs or the game output, checked
+/// in order so the most specific cause wins.
+const CRASH_CAUSE_MARKERS: [(CrashCause, &[&str]); 4] = [
+    (
+        CrashCause::OutOfMemory,
+        &[
+            "java.lang.OutOfMemoryError",
+            "Out of Memory Error",
+            "There is insufficient memory for the Java Runtime Environment",
+        ],
+    ),
+    (
+        CrashCause::WrongJavaVersion,
+        &[
+            "java.lang.UnsupportedClassVersionError",
+            "has been compiled by a more recent version of the Java Runtime",
+            "cannot be cast to class java.net.URLClassLoader",
+            "Unrecognized VM option",
+        ],
+    ),
+    (
+        CrashCause::MissingNatives,
+        &[
+            "java.lang.UnsatisfiedLinkError",
+            "in java.library.path",
+            "Failed to locate library",
+        ],
+    ),
+    (
+        CrashCause::GlContext,
+        &[
+            "GLFW error 65542",
+            "GLFW error 65543",
+            "Pixel format not accelerated",
+            "No OpenGL context found in the current thread",
+            "The driver does not appear to support OpenGL",
+        ],
+    ),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CrashCause {
+    OutOfMemory,
+    MissingNatives,
+    WrongJavaVersion,
+    GlContext,
+    Unknown,
+}
+
+/// What happened to a game session once the process is gone.
+#[derive(Debug, Clone)]
+pub struct GameExit {
+    /// `None` when the process was killed by a signal.
+    pub exit_code: Option<i32>,
+    pub crash_reports: Vec<String>,
+    pub jvm_crash_logs: Vec<String>,
+    pub crash_cause: Option<CrashCause>,
+    /// The launcher stopped the game, so a failing exit code is expected.
+    pub cancelled: bool,
+    /// Set when the post-exit hook failed, which does not change how the
+    /// game itself ended.
+    pub post_exit_hook_error: Option<String>,
+}
+
+impl GameExit {
+    pub fn crashed(&self) -> bool {
+        self.crash_cause.is_some()
+    }
+}
+
+/// Looks for crash reports and JVM crash logs written since `started_at` and
+/// classifies the failure from them and the last lines of game output.
+pub async fn analyze_game_exit(
+    game_directory: &str,
+    started_at: SystemTime,
+    status: ExitStatus,
+    cancelled: bool,
+    recent_output: &[String],
+) -> Result<GameExit> {
+    let crash_reports = find_new_files(
+        &join_paths(game_directory.to_string(), vec![CRASH_REPORTS_DIR]),
+        started_at,
+        |name| name.ends_with(".txt"),
+    )
+    .await?;
+    let jvm_crash_logs = find_new_files(game_directory, started_at, |name| {
+        name.starts_with(JVM_CRASH_LOG_PREFIX) && name.ends_with(".log")
+    })
+    .await?;
+
+    let crashed = (!status.success() && !cancelled)
+        || !crash_reports.is_empty()
+        || !jvm_crash_logs.is_empty();
+    let crash_cause = if crashed {
+        let mut sources = recent_output.to_vec();
+        for path in crash_reports.iter().chain(&jvm_crash_logs) {
+            let content = tokio::fs::read(path).await?;
+            sources.push(String::from_utf8_lossy(&content).to_string());
+        }
+
+        Some(classify_crash(&sources))
+    } else {
+        None
+    };
+
+    Ok(GameExit {
+        exit_code: status.code(),
+        crash_reports,
+        jvm_crash_logs,
+        crash_cause,
+        cancelled,
+        post_exit_hook_error: None,
+    })
+}
+
+fn classify_crash(sources: &[String]) -> CrashCause {
+    CRASH_CAUSE_MARKERS
+        .iter()
+        .find(|(_, markers)| {
+            sources
+                .iter()
+                .any(|source| markers.iter().any(|marker| source.contains(marker)))
+        })
+        .map(|(cause, _)| *cause)
+        .unwrap_or(CrashCause::Unknown)
+}
+
+async fn find_new_files(
+    directory: &str,
+    started_at: SystemTime,
+    filter: impl Fn(&str) -> bool,
+) -> Result<Vec<String>> {
+    if !Path::new(directory).exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    let mut entries = tokio::fs::read_dir(directory).await?;
+
+    while let Some(entry) = entries.next_entry().await? {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if !filter(&file_name) {
+            continue;
+        }
+
+        let modified = entry.metadata().await?.modified()?;
+        if modified >= started_at {
+            files.push(join_paths(directory.to_string(), vec![&file_name]));
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[cfg(unix)]
+    fn exit_status(code: i32) -> ExitStatus {
+        use std::os::unix::process::ExitStatusExt;
+
+        ExitStatus::from_raw(code << 8)
+    }
+
+    /// File times can lag the clock by a tick, so the game "started" a
+    /// moment earlier.
+    fn started_at() -> SystemTime {
+        SystemTime::now() - Duration::from_secs(1)
+    }
+
+    fn test_game_directory(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!(
+            "minecraft-launcher-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(path.join(CRASH_REPORTS_DIR)).unwrap();
+
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn classifies_crash_from_markers() {
+        let cases = [
+            (
+                "java.lang.OutOfMemoryError: Java heap space",
+                CrashCause::OutOfMemory,
+            ),
+            (
+                "java.lang.UnsatisfiedLinkError: no lwjgl in java.library.path",
+                CrashCause::MissingNatives,
+            ),
+            (
+                "has been compiled by a more recent version of the Java Runtime",
+                CrashCause::WrongJavaVersion,
+            ),
+            ("GLFW error 65542: WGL", CrashCause::GlContext),
+            ("Exception in thread \"main\"", CrashCause::Unknown),
+        ];
+
+        for (source, cause) in cases {
+            assert_eq!(classify_crash(&[source.to_string()]), cause, "{}", source);
+        }
+
+        // Running out of memory also breaks native loading, but it is the cause.
+        assert_eq!(
+            classify_crash(&[
+                "java.lang.UnsatisfiedLinkError".to_string(),
+                "java.lang.OutOfMemoryError".to_string(),
+            ]),
+            CrashCause::OutOfMemory
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn clean_exit_is_not_a_crash() {
+        let game_directory = test_game_directory("clean-exit");
+
+        let game_exit = analyze_game_exit(
+            &game_directory,
+            SystemTime::now(),
+            exit_status(0),
+            false,
+            &["[Render thread/INFO]: Stopping!".to_string()],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(game_exit.exit_code, Some(0));
+        assert!(!game_exit.crashed());
+        assert!(game_exit.crash_reports.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn classifies_crash_reports() {
+        let game_directory = test_game_directory("crash-report");
+        let started_at = started_at();
+        let report_path = join_paths(
+            game_directory.clone(),
+            vec![CRASH_REPORTS_DIR, "crash-2024-01-01_00.00.00-client.txt"],
+        );
+        std::fs::write(
+            &report_path,
+            "Description: Unexpected error\n\njava.lang.UnsatisfiedLinkError: Failed to locate library: liblwjgl.so",
+        )
+        .unwrap();
+
+        let game_exit =
+            analyze_game_exit(&game_directory, started_at, exit_status(255), false, &[])
+                .await
+                .unwrap();
+
+        assert_eq!(game_exit.crash_reports, vec![report_path]);
+        assert_eq!(game_exit.crash_cause, Some(CrashCause::MissingNatives));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn classifies_jvm_crash_logs() {
+        let game_directory = test_game_directory("jvm-crash-log");
+        let started_at = started_at();
+        let log_path = join_paths(game_directory.clone(), vec!["hs_err_pid4242.log"]);
+        std::fs::write(
+            &log_path,
+            "# There is insufficient memory for the Java Runtime Environment to continue.",
+        )
+        .unwrap();
+
+        let game_exit = analyze_game_exit(&game_directory, started_at, exit_status(1), false, &[])
+            .await
+            .unwrap();
+
+        assert_eq!(game_exit.jvm_crash_logs, vec![log_path]);
+        assert_eq!(game_exit.crash_cause, Some(CrashCause::OutOfMemory));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn classifies_out_of_memory_from_output() {
+        let game_directory = test_game_directory("out-of-memory");
+
+        let game_exit = analyze_game_exit(
+            &game_directory,
+            SystemTime::now(),
+            exit_status(1),
+            false,
+            &[
+                "Exception in thread \"Render thread\" java.lang.OutOfMemoryError: Java heap space"
+                    .to_string(),
+            ],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(game_exit.crash_cause, Some(CrashCause::OutOfMemory));
+        assert!(game_exit.crash_reports.is_empty());
+    }
+}
@@ -0,0 +1,94 @@
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameLogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+    Other(String),
+}
+
+/// A `<log4j:Event>` written by the game when the XML logging config is active.
+#[derive(Debug, Clone)]
+pub struct GameLogEvent {
+    pub logger: String,
+    pub level: GameLogLevel,
+    pub thread: String,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: u64,
+    pub message: String,
+    pub throwable: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameOutputStream {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Debug, Clone)]
+pub enum GameLogEntry {
+    Event(GameLogEvent),
+    /// Output that is not a log4j event, such as everything written before
+    /// logging is set up or by versions without an XML config.
+    Line {
+        stream: GameOutputStream,
+        line: String,
+    },
+}
+
+impl GameLogLevel {
+    pub fn parse(level: &str) -> Self {
+        match level {
+            "TRACE" => GameLogLevel::Trace,
+            "DEBUG" => GameLogLevel::Debug,
+            "INFO" => GameLogLevel::Info,
+            "WARN" => GameLogLevel::Warn,
+            "ERROR" => GameLogLevel::Error,
+            "FATAL" => GameLogLevel::Fatal,
+            _ => GameLogLevel::Other(level.to_string()),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            GameLogLevel::Trace => "TRACE",
+            GameLogLevel::Debug => "DEBUG",
+            GameLogLevel::Info => "INFO",
+            GameLogLevel::Warn => "WARN",
+            GameLogLevel::Error => "ERROR",
+            GameLogLevel::Fatal => "FATAL",
+            GameLogLevel::Other(level) => level,
+        }
+    }
+}
+
+impl GameLogEntry {
+    /// Formats the entry like the game's own console output.
+    pub fn format(&self) -> String {
+        match self {
+            GameLogEntry::Event(event) => {
+                let seconds_of_day = (event.timestamp / 1000) % 86400;
+                let mut formatted = format!(
+                    "[{:02}:{:02}:{:02}] [{}/{}] [{}]: {}",
+                    seconds_of_day / 3600,
+                    (seconds_of_day / 60) % 60,
+                    seconds_of_day % 60,
+                    event.thread,
+                    event.level.name(),
+                    event.logger,
+                    event.message
+                );
+
+                if let Some(throwable) = &event.throwable {
+                    formatted.push('\n');
+                    formatted.push_str(throwable.trim_end());
+                }
+
+                formatted
+            }
+            GameLogEntry::Line { line, .. } => line.clone(),
+        }
+    }
+}
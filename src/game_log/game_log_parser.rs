@@ -0,0 +1,226 @@
+use super::{GameLogEntry, GameLogEvent, GameLogLevel, GameOutputStream};
+
+const EVENT_START: &str = "<log4j:Event";
+const EVENT_END: &str = "</log4j:Event>";
+
+/// Turns the game's stdout, line by line, into log entries. Events of the
+/// log4j XML layout span several lines and are emitted once complete.
+#[derive(Debug, Default)]
+pub struct GameLogParser {
+    pending_event: Option<String>,
+}
+
+impl GameLogParser {
+    pub fn push_line(&mut self, line: String) -> Option<GameLogEntry> {
+        let pending_event = match self.pending_event.as_mut() {
+            Some(pending_event) => pending_event,
+            None if line.trim_start().starts_with(EVENT_START) => {
+                self.pending_event.insert(String::new())
+            }
+            None => {
+                return Some(GameLogEntry::Line {
+                    stream: GameOutputStream::Stdout,
+                    line,
+                })
+            }
+        };
+
+        pending_event.push_str(&line);
+        pending_event.push('\n');
+
+        if !line.contains(EVENT_END) {
+            return None;
+        }
+
+        let event = self.pending_event.take().unwrap_or_default();
+        match parse_event(&event) {
+            Some(event) => Some(GameLogEntry::Event(event)),
+            None => Some(GameLogEntry::Line {
+                stream: GameOutputStream::Stdout,
+                line: event,
+            }),
+        }
+    }
+
+    /// Returns what is left of an event the game did not finish writing.
+    pub fn finish(&mut self) -> Option<GameLogEntry> {
+        self.pending_event.take().map(|line| GameLogEntry::Line {
+            stream: GameOutputStream::Stdout,
+            line,
+        })
+    }
+}
+
+fn parse_event(event: &str) -> Option<GameLogEvent> {
+    let start = event.find(EVENT_START)? + EVENT_START.len();
+    let tag_end = start + event[start..].find('>')?;
+    let attributes = &event[start..tag_end];
+
+    Some(GameLogEvent {
+        logger: find_attribute(attributes, "logger").unwrap_or_default(),
+        level: GameLogLevel::parse(&find_attribute(attributes, "level").unwrap_or_default()),
+        thread: find_attribute(attributes, "thread").unwrap_or_default(),
+        timestamp: find_attribute(attributes, "timestamp")
+            .and_then(|timestamp| timestamp.parse().ok())
+            .unwrap_or_default(),
+        message: find_element(event, "log4j:Message").unwrap_or_default(),
+        throwable: find_element(event, "log4j:Throwable"),
+    })
+}
+
+fn find_attribute(attributes: &str, name: &str) -> Option<String> {
+    let pattern = format!(" {}=\"", name);
+    let start = attributes.find(&pattern)? + pattern.len();
+    let end = start + attributes[start..].find('"')?;
+
+    Some(unescape_xml(&attributes[start..end]))
+}
+
+fn find_element(event: &str, name: &str) -> Option<String> {
+    let open_tag = format!("<{}>", name);
+    let close_tag = format!("</{}>", name);
+
+    let start = event.find(&open_tag)? + open_tag.len();
+    let end = start + event[start..].find(&close_tag)?;
+    let content = &event[start..end];
+
+    match content
+        .trim()
+        .strip_prefix("<![CDATA[")
+        .and_then(|content| content.strip_suffix("]]>"))
+    {
+        Some(cdata) => Some(cdata.to_string()),
+        None => Some(unescape_xml(content.trim())),
+    }
+}
+
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_lines(parser: &mut GameLogParser, lines: &[&str]) -> Vec<GameLogEntry> {
+        lines
+            .iter()
+            .filter_map(|line| parser.push_line(line.to_string()))
+            .collect()
+    }
+
+    fn expect_event(entry: &GameLogEntry) -> &GameLogEvent {
+        match entry {
+            GameLogEntry::Event(event) => event,
+            other => panic!("expected an event, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_multi_line_event_with_cdata() {
+        let mut parser = GameLogParser::default();
+        let entries = push_lines(
+            &mut parser,
+            &[
+                r#"<log4j:Event logger="net.minecraft.client.Minecraft" timestamp="1700000000123" level="INFO" thread="Render thread">"#,
+                "  <log4j:Message><![CDATA[Setting user: <Notch> & friends]]></log4j:Message>",
+                "</log4j:Event>",
+            ],
+        );
+
+        assert_eq!(entries.len(), 1);
+        let event = expect_event(&entries[0]);
+        assert_eq!(event.logger, "net.minecraft.client.Minecraft");
+        assert_eq!(event.level, GameLogLevel::Info);
+        assert_eq!(event.thread, "Render thread");
+        assert_eq!(event.timestamp, 1700000000123);
+        assert_eq!(event.message, "Setting user: <Notch> & friends");
+        assert_eq!(event.throwable, None);
+    }
+
+    #[test]
+    fn parses_single_line_event_with_escaped_message() {
+        let mut parser = GameLogParser::default();
+        let entries = push_lines(
+            &mut parser,
+            &[
+                r#"<log4j:Event logger="a &amp; b" timestamp="0" level="WARN" thread="main"><log4j:Message>1 &lt; 2</log4j:Message></log4j:Event>"#,
+            ],
+        );
+
+        let event = expect_event(&entries[0]);
+        assert_eq!(event.logger, "a & b");
+        assert_eq!(event.level, GameLogLevel::Warn);
+        assert_eq!(event.message, "1 < 2");
+    }
+
+    #[test]
+    fn keeps_throwable_lines() {
+        let mut parser = GameLogParser::default();
+        let entries = push_lines(
+            &mut parser,
+            &[
+                r#"<log4j:Event logger="Worker" timestamp="0" level="ERROR" thread="Worker-1">"#,
+                "  <log4j:Message><![CDATA[Failed]]></log4j:Message>",
+                "  <log4j:Throwable><![CDATA[java.lang.IllegalStateException: boom",
+                "\tat Foo.bar(Foo.java:1)",
+                "]]></log4j:Throwable>",
+                "</log4j:Event>",
+            ],
+        );
+
+        let event = expect_event(&entries[0]);
+        assert_eq!(event.level, GameLogLevel::Error);
+        assert_eq!(
+            event.throwable.as_deref(),
+            Some("java.lang.IllegalStateException: boom\n\tat Foo.bar(Foo.java:1)\n")
+        );
+    }
+
+    #[test]
+    fn passes_plain_lines_through() {
+        let mut parser = GameLogParser::default();
+        let entries = push_lines(&mut parser, &["Loading natives", "  indented"]);
+
+        assert!(matches!(
+            &entries[..],
+            [
+                GameLogEntry::Line { line: first, stream: GameOutputStream::Stdout },
+                GameLogEntry::Line { line: second, .. },
+            ] if first == "Loading natives" && second == "  indented"
+        ));
+    }
+
+    #[test]
+    fn returns_unfinished_event_on_finish() {
+        let mut parser = GameLogParser::default();
+        let entries = push_lines(
+            &mut parser,
+            &[
+                r#"<log4j:Event logger="a" timestamp="0" level="INFO" thread="main">"#,
+                "  <log4j:Message><![CDATA[cut",
+            ],
+        );
+        assert!(entries.is_empty());
+
+        match parser.finish() {
+            Some(GameLogEntry::Line { line, .. }) => assert!(line.contains("cut")),
+            other => panic!("expected the partial event, got {:?}", other),
+        }
+        assert!(parser.finish().is_none());
+    }
+
+    #[test]
+    fn parses_unknown_levels() {
+        assert_eq!(
+            GameLogLevel::parse("NOTICE"),
+            GameLogLevel::Other("NOTICE".to_string())
+        );
+        assert_eq!(GameLogLevel::parse("FATAL").name(), "FATAL");
+    }
+}
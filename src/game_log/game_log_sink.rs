@@ -0,0 +1,144 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::Path,
+};
+
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+use super::{GameLogEntry, GameLogLevel, GameOutputStream};
+use crate::{channel::send_to_listener, Result};
+
+/// Receives everything the game writes while it runs.
+pub trait GameLogSink: Send {
+    fn handle(&mut self, entry: GameLogEntry) -> Result<()>;
+}
+
+/// Prints entries to the launcher's own console, coloring warnings and
+/// errors.
+#[derive(Debug, Default)]
+pub struct ConsoleLogSink;
+
+impl GameLogSink for ConsoleLogSink {
+    fn handle(&mut self, entry: GameLogEntry) -> Result<()> {
+        let color = match &entry {
+            GameLogEntry::Event(event) => match event.level {
+                GameLogLevel::Warn => Some("\x1b[33m"),
+                GameLogLevel::Error | GameLogLevel::Fatal => Some("\x1b[31m"),
+                GameLogLevel::Trace | GameLogLevel::Debug => Some("\x1b[90m"),
+                _ => None,
+            },
+            GameLogEntry::Line {
+                stream: GameOutputStream::Stderr,
+                ..
+            } => Some("\x1b[31m"),
+            GameLogEntry::Line { .. } => None,
+        };
+
+        match color {
+            Some(color) => println!("{}{}\x1b[0m", color, entry.format()),
+            None => println!("{}", entry.format()),
+        }
+
+        Ok(())
+    }
+}
+
+/// Appends entries to a file, moving it to `<path>.1`, `<path>.2`, ... once
+/// it grows past `max_file_size`.
+pub struct RotatingFileLogSink {
+    path: String,
+    max_file_size: u64,
+    max_backups: usize,
+    file: Option<File>,
+    file_size: u64,
+}
+
+impl RotatingFileLogSink {
+    pub fn new(path: String, max_file_size: u64, max_backups: usize) -> Self {
+        Self {
+            path,
+            max_file_size,
+            max_backups,
+            file: None,
+            file_size: 0,
+        }
+    }
+
+    fn open_file(&mut self) -> Result<&mut File> {
+        if self.file.is_none() {
+            if let Some(parent) = Path::new(&self.path).parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+            self.file_size = file.metadata()?.len();
+            self.file = Some(file);
+        }
+
+        Ok(self.file.as_mut().unwrap())
+    }
+
+    fn rotate(&mut self) -> Result<()> {
+        self.file = None;
+
+        if self.max_backups == 0 {
+            std::fs::remove_file(&self.path)?;
+            return Ok(());
+        }
+
+        for index in (1..self.max_backups).rev() {
+            let backup_path = self.backup_path(index);
+            if Path::new(&backup_path).exists() {
+                std::fs::rename(backup_path, self.backup_path(index + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, self.backup_path(1))?;
+
+        Ok(())
+    }
+
+    fn backup_path(&self, index: usize) -> String {
+        format!("{}.{}", self.path, index)
+    }
+}
+
+impl GameLogSink for RotatingFileLogSink {
+    fn handle(&mut self, entry: GameLogEntry) -> Result<()> {
+        let line = format!("{}\n", entry.format());
+
+        // `open_file` sets `file_size` from the file already on disk.
+        self.open_file()?;
+        if self.file_size > 0 && self.file_size + line.len() as u64 > self.max_file_size {
+            self.rotate()?;
+        }
+
+        self.open_file()?.write_all(line.as_bytes())?;
+        self.file_size += line.len() as u64;
+
+        Ok(())
+    }
+}
+
+/// Forwards entries to a channel, for library consumers that render the
+/// log themselves.
+pub struct ChannelLogSink {
+    sender: UnboundedSender<GameLogEntry>,
+}
+
+impl ChannelLogSink {
+    pub fn new() -> (Self, UnboundedReceiver<GameLogEntry>) {
+        let (sender, receiver) = unbounded_channel();
+        (Self { sender }, receiver)
+    }
+}
+
+impl GameLogSink for ChannelLogSink {
+    fn handle(&mut self, entry: GameLogEntry) -> Result<()> {
+        send_to_listener(&self.sender, entry);
+        Ok(())
+    }
+}
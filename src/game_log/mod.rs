@@ -0,0 +1,7 @@
+mod game_log_entry;
+mod game_log_parser;
+mod game_log_sink;
+
+pub use game_log_entry::*;
+pub use game_log_parser::*;
+pub use game_log_sink::*;
@@ -1,23 +1,409 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, LazyLock, Mutex, RwLock},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use reqwest::{
+    header::{CONTENT_RANGE, RANGE, RETRY_AFTER},
+    Response, StatusCode,
+};
 use serde::de::DeserializeOwned;
+use sha1::{Digest, Sha1};
+use tokio::{io::AsyncWriteExt, sync::Semaphore};
+
+use crate::{
+    files::{create_parent_folders, update_sha1_from_file},
+    Result,
+};
+
+static HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(reqwest::Client::new);
+static DOWNLOAD_POLICY: LazyLock<RwLock<DownloadPolicy>> = LazyLock::new(Default::default);
+static HOST_PERMITS: LazyLock<Mutex<HashMap<String, Arc<Semaphore>>>> =
+    LazyLock::new(Default::default);
+
+/// How every request of the launcher deals with transient failures.
+#[derive(Debug, Clone)]
+pub struct DownloadPolicy {
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled on each following one.
+    pub initial_backoff: Duration,
+    /// Upper bound of the backoff, also applied to `Retry-After`.
+    pub max_backoff: Duration,
+    pub max_connections_per_host: usize,
+}
+
+impl Default for DownloadPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            max_connections_per_host: 8,
+        }
+    }
+}
+
+impl DownloadPolicy {
+    /// Exponential backoff with jitter, so parallel downloads failing
+    /// together do not retry in lockstep.
+    fn backoff(&self, retry: u32) -> Duration {
+        let delay = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff);
+
+        delay / 2 + delay.mul_f64(fastrand::f64() / 2.0)
+    }
+}
+
+/// A file that could not be downloaded, reported instead of aborting the
+/// downloads that were still running.
+#[derive(Debug)]
+pub struct DownloadFailure {
+    pub path: String,
+    pub url: String,
+    pub error: crate::Error,
+}
+
+/// What `download_to_file` reports while it runs.
+#[derive(Debug, Clone)]
+pub enum TransferEvent {
+    /// Bytes of the file written so far.
+    Progress(u64),
+    /// A transient failure, retried after `delay`.
+    Retrying {
+        retry: u32,
+        delay: Duration,
+        error: String,
+    },
+}
+
+/// Size and SHA-1 of what `download_to_file` wrote, computed while streaming.
+#[derive(Debug, Clone)]
+pub struct DownloadedFile {
+    pub size: u64,
+    pub sha1: String,
+}
+
+pub fn download_policy() -> DownloadPolicy {
+    DOWNLOAD_POLICY.read().unwrap().clone()
+}
 
-use crate::Result;
+pub fn set_download_policy(policy: DownloadPolicy) {
+    *DOWNLOAD_POLICY.write().unwrap() = policy;
+    // Hosts pick up the new connection limit on their next request.
+    HOST_PERMITS.lock().unwrap().clear();
+}
+
+/// Turns the failures collected from a batch of downloads into an error.
+pub fn check_download_failures(failures: Vec<DownloadFailure>) -> Result<()> {
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(crate::Error::DownloadsFailed(failures))
+    }
+}
 
 pub async fn download_serialized<T>(url: String) -> Result<T>
 where
     T: DeserializeOwned,
 {
-    let response = reqwest::get(url).await?;
+    let content = with_retries(&url, &|_| {}, |_| fetch_bytes(&url)).await?;
 
-    response.json::<T>().await.map_err(crate::Error::from)
+    Ok(serde_json::from_slice(&content)?)
 }
 
 pub async fn download(url: String) -> Result<Vec<u8>> {
-    println!("Starting to download form url {}", url.clone());
-    let response = reqwest::get(url).await?;
+    with_retries(&url, &|_| {}, |_| fetch_bytes(&url)).await
+}
+
+/// Streams the response to `path` chunk by chunk instead of buffering it.
+/// With `resume`, an existing partial file is continued with a `Range`
+/// request; servers that ignore or reject the range get a fresh download.
+/// Retries always resume, since the partial file then comes from this call.
+pub async fn download_to_file(
+    url: String,
+    path: &str,
+    resume: bool,
+    on_event: &(dyn Fn(TransferEvent) + Sync),
+) -> Result<DownloadedFile> {
+    let url = url.as_str();
+    with_retries(url, on_event, move |retry| {
+        try_download_to_file(url, path, resume || retry > 0, on_event)
+    })
+    .await
+}
+
+async fn fetch_bytes(url: &str) -> Result<Vec<u8>> {
+    let response = check_status(url, HTTP_CLIENT.get(url).send().await?)?;
+
+    Ok(response.bytes().await?.to_vec())
+}
+
+async fn try_download_to_file(
+    url: &str,
+    path: &str,
+    resume: bool,
+    on_event: &(dyn Fn(TransferEvent) + Sync),
+) -> Result<DownloadedFile> {
+    let existing_size = match tokio::fs::metadata(path).await {
+        Ok(metadata) if resume => metadata.len(),
+        _ => 0,
+    };
+
+    let response = send_download_request(url, existing_size).await?;
+    let (response, resume_from) = if existing_size == 0 {
+        (response, 0)
+    } else if is_resumed_response(&response, existing_size) {
+        (response, existing_size)
+    } else if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        (send_download_request(url, 0).await?, 0)
+    } else {
+        (response, 0)
+    };
+    let mut response = check_status(url, response)?;
+
+    create_parent_folders(path.to_string()).await?;
+    let mut hasher = Sha1::new();
+    let mut file = if resume_from > 0 {
+        update_sha1_from_file(&mut hasher, path).await?;
+        tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(path)
+            .await?
+    } else {
+        tokio::fs::File::create(path).await?
+    };
+    let mut size = resume_from;
+
+    while let Some(chunk) = response.chunk().await? {
+        hasher.update(&chunk);
+        file.write_all(&chunk).await?;
+        size += chunk.len() as u64;
+        on_event(TransferEvent::Progress(size));
+    }
+    file.sync_all().await?;
+
+    Ok(DownloadedFile {
+        size,
+        sha1: format!("{:x}", hasher.finalize()),
+    })
+}
+
+async fn send_download_request(url: &str, range_start: u64) -> Result<Response> {
+    let mut request = HTTP_CLIENT.get(url);
+    if range_start > 0 {
+        request = request.header(RANGE, format!("bytes={}-", range_start));
+    }
+
+    Ok(request.send().await?)
+}
+
+/// A server honouring the range answers `206` with a `Content-Range`
+/// starting where the partial file ends.
+fn is_resumed_response(response: &Response, range_start: u64) -> bool {
+    if response.status() != StatusCode::PARTIAL_CONTENT {
+        return false;
+    }
 
     response
-        .bytes()
-        .await
-        .map(|bytes| bytes.to_vec())
-        .map_err(crate::Error::from)
+        .headers()
+        .get(CONTENT_RANGE)
+        .and_then(|content_range| content_range.to_str().ok())
+        .and_then(|content_range| content_range.strip_prefix("bytes "))
+        .and_then(|content_range| content_range.split('-').next())
+        .and_then(|start| start.parse::<u64>().ok())
+        == Some(range_start)
+}
+
+/// Runs `attempt` until it succeeds, fails with an error that retrying will
+/// not fix, or runs out of retries. Each attempt holds a connection permit
+/// of the url's host, and each retry is reported to `on_event`.
+async fn with_retries<T, F, Fut>(
+    url: &str,
+    on_event: &(dyn Fn(TransferEvent) + Sync),
+    mut attempt: F,
+) -> Result<T>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let policy = download_policy();
+    let host_permits = host_permits(url, policy.max_connections_per_host);
+    let mut retry = 0;
+
+    loop {
+        let result = {
+            let _permit = host_permits.acquire().await;
+            attempt(retry).await
+        };
+
+        let error = match result {
+            Ok(value) => return Ok(value),
+            Err(error) if retry < policy.max_retries && is_transient(&error) => error,
+            Err(error) => return Err(error),
+        };
+
+        let delay = match &error {
+            crate::Error::HttpStatusError {
+                retry_after: Some(retry_after),
+                ..
+            } => Duration::from_secs(*retry_after).min(policy.max_backoff),
+            _ => policy.backoff(retry),
+        };
+        retry += 1;
+        on_event(TransferEvent::Retrying {
+            retry,
+            delay,
+            error: format!("{:?}", error),
+        });
+
+        tokio::time::sleep(delay).await;
+    }
+}
+
+fn is_transient(error: &crate::Error) -> bool {
+    match error {
+        crate::Error::HttpStatusError { status, .. } => {
+            matches!(status, 408 | 429 | 500 | 502 | 503 | 504)
+        }
+        crate::Error::RequestError(error) => {
+            error.is_timeout() || error.is_connect() || error.is_request() || error.is_body()
+        }
+        _ => false,
+    }
+}
+
+fn host_permits(url: &str, max_connections_per_host: usize) -> Arc<Semaphore> {
+    let host = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(|host| host.to_string()))
+        .unwrap_or_default();
+
+    HOST_PERMITS
+        .lock()
+        .unwrap()
+        .entry(host)
+        .or_insert_with(|| Arc::new(Semaphore::new(max_connections_per_host.max(1))))
+        .clone()
+}
+
+fn check_status(url: &str, response: Response) -> Result<Response> {
+    let status = response.status();
+    if !status.is_client_error() && !status.is_server_error() {
+        return Ok(response);
+    }
+
+    let retry_after = response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|retry_after| retry_after.to_str().ok())
+        .and_then(parse_retry_after);
+
+    Err(crate::Error::HttpStatusError {
+        url: url.to_string(),
+        status: status.as_u16(),
+        retry_after,
+    })
+}
+
+/// `Retry-After` holds either delta-seconds or an HTTP-date. Of the date
+/// formats only the IMF-fixdate servers are required to send is understood;
+/// the obsolete RFC 850 and asctime forms fall back to the backoff.
+fn parse_retry_after(value: &str) -> Option<u64> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse() {
+        return Some(seconds);
+    }
+
+    let retry_at = parse_http_date(value)?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+
+    Some(retry_at.saturating_sub(now))
+}
+
+/// Seconds since the Unix epoch of an IMF-fixdate such as
+/// `Sun, 06 Nov 1994 08:49:37 GMT`.
+fn parse_http_date(value: &str) -> Option<u64> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    let (_, date) = value.strip_suffix(" GMT")?.split_once(", ")?;
+    let mut parts = date.split(' ');
+    let day: u64 = parts.next()?.parse().ok()?;
+    let month_name = parts.next()?;
+    let month = MONTHS.iter().position(|month| *month == month_name)? as u64 + 1;
+    let year: u64 = parts.next()?.parse().ok()?;
+    let mut time = parts
+        .next()?
+        .split(':')
+        .map(|part| part.parse::<u64>().ok());
+    let (hours, minutes, seconds) = (time.next()??, time.next()??, time.next()??);
+    if parts.next().is_some() || time.next().is_some() {
+        return None;
+    }
+    if day > 31 || hours > 23 || minutes > 59 || seconds > 60 {
+        return None;
+    }
+
+    // Days from civil date, counting years from March so leap days come last.
+    // Dates before year 1 or on day 0 are out of range, not a delay.
+    let year = if month <= 2 {
+        year.checked_sub(1)?
+    } else {
+        year
+    };
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day.checked_sub(1)?;
+    let day_of_era = year % 400 * 365 + year % 400 / 4 - year % 400 / 100 + day_of_year;
+    let days = (year / 400)
+        .checked_mul(146_097)?
+        .checked_add(day_of_era)?
+        .checked_sub(719_468)?;
+
+    days.checked_mul(86_400)?
+        .checked_add(hours * 3_600 + minutes * 60 + seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_retry_after_seconds_and_dates() {
+        assert_eq!(parse_retry_after(" 120 "), Some(120));
+        assert_eq!(
+            parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(784_111_777)
+        );
+        assert_eq!(
+            parse_http_date("Thu, 29 Feb 2024 23:59:59 GMT"),
+            Some(1_709_251_199)
+        );
+        assert_eq!(parse_retry_after("Sun, 06 Nov 1994 08:49:37 GMT"), Some(0));
+    }
+
+    #[test]
+    fn ignores_obsolete_and_invalid_retry_after() {
+        assert_eq!(parse_retry_after("Sunday, 06-Nov-94 08:49:37 GMT"), None);
+        assert_eq!(parse_retry_after("Sun Nov  6 08:49:37 1994"), None);
+        assert_eq!(parse_retry_after("Sun, 06 Foo 1994 08:49:37 GMT"), None);
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[test]
+    fn ignores_out_of_range_dates() {
+        assert_eq!(parse_http_date("Sat, 01 Jan 0000 00:00:00 GMT"), None);
+        assert_eq!(parse_http_date("Sun, 00 Nov 1994 08:49:37 GMT"), None);
+        assert_eq!(
+            parse_http_date("Sun, 06 Nov 18446744073709551615 08:49:37 GMT"),
+            None
+        );
+        assert_eq!(
+            parse_http_date("Sun, 06 Nov 1994 18446744073709551615:49:37 GMT"),
+            None
+        );
+    }
 }
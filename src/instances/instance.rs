@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    jvm_settings::JvmSettings, launch_hooks::LaunchHooks, launch_options::LaunchOptions,
+    launcher_paths::join_paths, LauncherPaths,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ModLoaderKind {
+    Fabric,
+    Quilt,
+    Forge,
+    NeoForge,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ModLoader {
+    pub kind: ModLoaderKind,
+    /// Fabric and Quilt fall back to the latest stable loader when empty;
+    /// Forge and NeoForge need their Maven version.
+    pub version: Option<String>,
+}
+
+/// Which latest version a profile of the official launcher follows.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LatestVersion {
+    Release,
+    Snapshot,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct InstanceSettings {
+    #[serde(default)]
+    pub launch_options: LaunchOptions,
+    #[serde(default)]
+    pub jvm: JvmSettings,
+    #[serde(default)]
+    pub hooks: LaunchHooks,
+}
+
+/// A game setup with its own game directory, saved as `instance.json` in the
+/// instance folder. Versions, libraries, assets and runtimes stay shared.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Instance {
+    pub id: String,
+    pub name: String,
+    /// Minecraft version the instance is pinned to.
+    pub version_id: String,
+    pub mod_loader: Option<ModLoader>,
+    #[serde(default)]
+    pub settings: InstanceSettings,
+    /// Version written by the mod loader installer, set once it ran.
+    pub installed_version_id: Option<String>,
+    /// Game directory outside the instance folder, kept by profiles imported
+    /// from the official launcher.
+    pub game_directory: Option<String>,
+    /// Key of the `launcher_profiles.json` profile the instance was imported
+    /// from, so importing again does not duplicate it.
+    pub launcher_profile_key: Option<String>,
+    /// Set when the source profile follows the latest release or snapshot,
+    /// so exporting writes it back as such instead of pinning `version_id`.
+    pub launcher_profile_latest: Option<LatestVersion>,
+}
+
+impl Instance {
+    /// The version to select when launching this instance.
+    pub fn launch_version_id(&self) -> &str {
+        match &self.mod_loader {
+            Some(_) => self
+                .installed_version_id
+                .as_deref()
+                .unwrap_or(&self.version_id),
+            None => &self.version_id,
+        }
+    }
+
+    pub fn game_directory_path(&self, launcher_paths: &LauncherPaths) -> String {
+        match &self.game_directory {
+            Some(game_directory) => game_directory.clone(),
+            None => launcher_paths.build_instance_game_dir_path(&self.id),
+        }
+    }
+
+    /// Where versions whose assets map to resources read them from.
+    pub fn resources_dir_path(&self, launcher_paths: &LauncherPaths) -> String {
+        join_paths(self.game_directory_path(launcher_paths), vec!["resources"])
+    }
+
+    pub fn needs_mod_loader_install(&self) -> bool {
+        self.mod_loader.is_some() && self.installed_version_id.is_none()
+    }
+}
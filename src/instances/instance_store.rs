@@ -0,0 +1,209 @@
+use std::path::Path;
+
+use crate::{
+    files::{load_serialized_file, save_serialized_file},
+    LauncherPath, LauncherPaths, Result,
+};
+
+use super::{Instance, InstanceSettings, ModLoader};
+
+#[derive(Debug)]
+pub struct InstanceStore {
+    pub(super) launcher_paths: LauncherPaths,
+    pub(super) instances: Vec<Instance>,
+}
+
+impl InstanceStore {
+    /// Reads every `instances/<id>/instance.json`; folders without one are
+    /// ignored.
+    pub async fn load(launcher_paths: LauncherPaths) -> Result<Self> {
+        let mut instances = Vec::new();
+
+        let instances_dir = launcher_paths.get_path(LauncherPath::InstancesDir);
+        if Path::new(&instances_dir).exists() {
+            let mut entries = tokio::fs::read_dir(instances_dir).await?;
+
+            while let Some(entry) = entries.next_entry().await? {
+                let instance_id = entry.file_name().to_string_lossy().to_string();
+                let instance_file_path = launcher_paths.build_instance_file_path(&instance_id);
+
+                if let Some(instance) = load_serialized_file(instance_file_path).await? {
+                    instances.push(instance);
+                }
+            }
+        }
+
+        instances.sort_by(|a: &Instance, b: &Instance| a.name.cmp(&b.name));
+
+        Ok(Self {
+            launcher_paths,
+            instances,
+        })
+    }
+
+    pub fn instances(&self) -> &[Instance] {
+        &self.instances
+    }
+
+    pub fn find_instance(&self, instance_id: &str) -> Option<&Instance> {
+        self.instances
+            .iter()
+            .find(|instance| instance.id == instance_id)
+    }
+
+    pub async fn create_instance(
+        &mut self,
+        name: String,
+        version_id: String,
+        mod_loader: Option<ModLoader>,
+    ) -> Result<Instance> {
+        let instance = Instance {
+            id: self.unique_instance_id(&name),
+            name,
+            version_id,
+            mod_loader,
+            settings: InstanceSettings::default(),
+            installed_version_id: None,
+            game_directory: None,
+            launcher_profile_key: None,
+            launcher_profile_latest: None,
+        };
+
+        tokio::fs::create_dir_all(
+            self.launcher_paths
+                .build_instance_game_dir_path(&instance.id),
+        )
+        .await?;
+        self.save_instance(instance.clone()).await?;
+
+        Ok(instance)
+    }
+
+    /// Moves the `game` folder that launchers before instances shared between
+    /// all versions into a new "Default" instance, so its saves, mods and
+    /// options are not left behind. Returns the new instance when there was a
+    /// folder to move.
+    pub async fn migrate_legacy_game_dir(
+        &mut self,
+        version_id: String,
+    ) -> Result<Option<Instance>> {
+        let legacy_game_dir = self.launcher_paths.get_path(LauncherPath::LegacyGameDir);
+        if !Path::new(&legacy_game_dir).is_dir() {
+            return Ok(None);
+        }
+
+        let instance = self
+            .create_instance("Default".to_string(), version_id, None)
+            .await?;
+        let game_dir = self
+            .launcher_paths
+            .build_instance_game_dir_path(&instance.id);
+        tokio::fs::remove_dir(&game_dir).await?;
+        tokio::fs::rename(legacy_game_dir, game_dir).await?;
+
+        Ok(Some(instance))
+    }
+
+    /// Writes the instance to its `instance.json`, adding it to the store if
+    /// it is new.
+    pub async fn save_instance(&mut self, instance: Instance) -> Result<()> {
+        save_serialized_file(
+            self.launcher_paths.build_instance_file_path(&instance.id),
+            &instance,
+        )
+        .await?;
+
+        match self
+            .instances
+            .iter_mut()
+            .find(|stored| stored.id == instance.id)
+        {
+            Some(stored) => *stored = instance,
+            None => self.instances.push(instance),
+        }
+
+        Ok(())
+    }
+
+    /// Deletes the instance folder, including its saves and mods.
+    pub async fn remove_instance(&mut self, instance_id: &str) -> Result<()> {
+        let instance_index = self
+            .instances
+            .iter()
+            .position(|instance| instance.id == instance_id)
+            .ok_or(crate::Error::InstanceNotFound(instance_id.to_string()))?;
+
+        tokio::fs::remove_dir_all(self.launcher_paths.build_instance_dir_path(instance_id)).await?;
+        self.instances.remove(instance_index);
+
+        Ok(())
+    }
+
+    /// Derives a folder-safe id from the name, suffixed when already taken.
+    fn unique_instance_id(&self, name: &str) -> String {
+        let slug = name
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect::<String>()
+            .split('-')
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("-");
+        let base_id = if slug.is_empty() {
+            "instance".to_string()
+        } else {
+            slug
+        };
+
+        let mut instance_id = base_id.clone();
+        let mut suffix = 2;
+        while self.find_instance(&instance_id).is_some()
+            || Path::new(&self.launcher_paths.build_instance_dir_path(&instance_id)).exists()
+        {
+            instance_id = format!("{}-{}", base_id, suffix);
+            suffix += 1;
+        }
+
+        instance_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn moves_the_legacy_game_dir_into_an_instance() {
+        let base_path = std::env::temp_dir().join(format!(
+            "minecraft-launcher-legacy-game-dir-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&base_path);
+        let launcher_paths = LauncherPaths::new(base_path.to_string_lossy().to_string());
+        let legacy_saves = Path::new(&launcher_paths.get_path(LauncherPath::LegacyGameDir))
+            .join("saves")
+            .join("World");
+        std::fs::create_dir_all(&legacy_saves).unwrap();
+
+        let mut store = InstanceStore::load(launcher_paths.clone()).await.unwrap();
+        let instance = store
+            .migrate_legacy_game_dir("1.20.1".to_string())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let game_dir = instance.game_directory_path(&launcher_paths);
+        assert!(Path::new(&game_dir).join("saves").join("World").is_dir());
+        assert!(!legacy_saves.exists());
+        assert_eq!(instance.version_id, "1.20.1");
+
+        let reloaded = InstanceStore::load(launcher_paths).await.unwrap();
+        assert_eq!(reloaded.instances().len(), 1);
+        assert!(store
+            .migrate_legacy_game_dir("1.20.1".to_string())
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,8 @@
+mod instance;
+mod instance_store;
+mod prism_import;
+mod vanilla_profiles;
+
+pub use instance::*;
+pub use instance_store::*;
+pub use prism_import::*;
@@ -0,0 +1,358 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use zip::ZipArchive;
+
+use crate::{
+    files::copy_dir_all,
+    jvm_settings::{split_jvm_arguments, JvmSettings},
+    model::prism::MmcPack,
+    Result,
+};
+
+use super::{Instance, InstanceStore, ModLoader, ModLoaderKind};
+
+const INSTANCE_CFG_FILE: &str = "instance.cfg";
+const MMC_PACK_FILE: &str = "mmc-pack.json";
+/// Newer instances use `.minecraft`, older MultiMC ones `minecraft`.
+const GAME_DIR_NAMES: [&str; 2] = [".minecraft", "minecraft"];
+
+const MINECRAFT_UID: &str = "net.minecraft";
+const MOD_LOADER_UIDS: [(&str, ModLoaderKind); 4] = [
+    ("net.fabricmc.fabric-loader", ModLoaderKind::Fabric),
+    ("org.quiltmc.quilt-loader", ModLoaderKind::Quilt),
+    ("net.minecraftforge", ModLoaderKind::Forge),
+    ("net.neoforged", ModLoaderKind::NeoForge),
+];
+
+/// What an instance of Prism Launcher or MultiMC maps to in this launcher.
+#[derive(Debug, Clone)]
+pub struct PrismInstance {
+    pub name: Option<String>,
+    pub version_id: String,
+    pub mod_loader: Option<ModLoader>,
+    pub jvm: JvmSettings,
+}
+
+impl PrismInstance {
+    /// Reads `instance.cfg` and `mmc-pack.json`. Components other than
+    /// Minecraft and its mod loaders, such as LWJGL or Fabric intermediary
+    /// mappings, are resolved by the loader installers and skipped here.
+    pub fn parse(instance_cfg: &str, mmc_pack: &str) -> Result<Self> {
+        let settings = parse_instance_cfg(instance_cfg);
+        let mmc_pack: MmcPack = serde_json::from_str(mmc_pack)?;
+
+        let version_id = mmc_pack
+            .components
+            .iter()
+            .find(|component| component.uid == MINECRAFT_UID)
+            .and_then(|component| component.resolved_version())
+            .ok_or(crate::Error::InvalidInstanceImport(format!(
+                "{} has no {} component",
+                MMC_PACK_FILE, MINECRAFT_UID
+            )))?
+            .to_string();
+
+        let mod_loader = mmc_pack.components.iter().find_map(|component| {
+            let (_, kind) = MOD_LOADER_UIDS
+                .iter()
+                .find(|(uid, _)| *uid == component.uid)?;
+            let version = component.resolved_version().map(|version| match kind {
+                // Prism stores the Forge build alone, its Maven version is
+                // prefixed with the Minecraft version.
+                ModLoaderKind::Forge if !version.starts_with(&version_id) => {
+                    format!("{}-{}", version_id, version)
+                }
+                _ => version.to_string(),
+            });
+
+            Some(ModLoader {
+                kind: *kind,
+                version,
+            })
+        });
+
+        let is_enabled = |key: &str| settings.get(key).is_some_and(|value| value == "true");
+        let mut jvm = JvmSettings::default();
+        if is_enabled("OverrideMemory") {
+            jvm.min_memory_mb = settings
+                .get("MinMemAlloc")
+                .and_then(|value| value.parse().ok());
+            jvm.max_memory_mb = settings
+                .get("MaxMemAlloc")
+                .and_then(|value| value.parse().ok());
+        }
+        if is_enabled("OverrideJavaArgs") {
+            jvm.extra_arguments = settings
+                .get("JvmArgs")
+                .map(|value| split_jvm_arguments(value))
+                .unwrap_or_default();
+        }
+
+        Ok(Self {
+            name: settings
+                .get("name")
+                .filter(|name| !name.is_empty())
+                .cloned(),
+            version_id,
+            mod_loader,
+            jvm,
+        })
+    }
+}
+
+impl InstanceStore {
+    /// Imports an instance folder of Prism Launcher or MultiMC, or a zip
+    /// exported from them, copying its game directory into a new instance.
+    pub async fn import_prism_instance(&mut self, source: &str) -> Result<Instance> {
+        let source_path = PathBuf::from(source);
+        let is_archive = source_path.is_file();
+
+        let (instance_cfg, mmc_pack) = if is_archive {
+            let source_path = source_path.clone();
+            tokio::task::spawn_blocking(move || read_archive_instance_files(&source_path)).await??
+        } else {
+            (
+                read_instance_file(&source_path, INSTANCE_CFG_FILE).await?,
+                read_instance_file(&source_path, MMC_PACK_FILE).await?,
+            )
+        };
+        let prism_instance = PrismInstance::parse(&instance_cfg, &mmc_pack)?;
+
+        let name = prism_instance.name.clone().unwrap_or_else(|| {
+            source_path
+                .file_stem()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default()
+        });
+        let mut instance = self
+            .create_instance(name, prism_instance.version_id, prism_instance.mod_loader)
+            .await?;
+        instance.settings.jvm = prism_instance.jvm;
+
+        let game_directory = self
+            .launcher_paths
+            .build_instance_game_dir_path(&instance.id);
+        let copy_result = if is_archive {
+            let target_path = PathBuf::from(game_directory);
+            tokio::task::spawn_blocking(move || {
+                extract_archive_game_dir(&source_path, &target_path)
+            })
+            .await
+            .map_err(crate::Error::from)
+            .and_then(|result| result)
+        } else {
+            copy_game_dir(&source_path, &game_directory).await
+        };
+
+        // Do not leave a half copied instance behind.
+        if let Err(err) = copy_result {
+            self.remove_instance(&instance.id).await?;
+            return Err(err);
+        }
+
+        self.save_instance(instance.clone()).await?;
+
+        Ok(instance)
+    }
+}
+
+/// Parses the `key=value` lines of `instance.cfg`, ignoring sections.
+/// Values quoted by Qt are unquoted.
+fn parse_instance_cfg(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with(['[', '#', ';']))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            let value = value.trim();
+            let value = match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+                Some(quoted) => quoted.replace("\\\"", "\"").replace("\\\\", "\\"),
+                None => value.to_string(),
+            };
+            (key.trim().to_string(), value)
+        })
+        .collect()
+}
+
+async fn read_instance_file(instance_dir: &Path, file_name: &str) -> Result<String> {
+    let path = instance_dir.join(file_name);
+    if !path.is_file() {
+        return Err(crate::Error::InvalidInstanceImport(format!(
+            "{} not found in {}",
+            file_name,
+            instance_dir.display()
+        )));
+    }
+
+    Ok(tokio::fs::read_to_string(path).await?)
+}
+
+async fn copy_game_dir(instance_dir: &Path, target: &str) -> Result<()> {
+    for game_dir_name in GAME_DIR_NAMES {
+        let game_dir = instance_dir.join(game_dir_name);
+        if game_dir.is_dir() {
+            return copy_dir_all(&game_dir.to_string_lossy(), target, true).await;
+        }
+    }
+
+    Ok(())
+}
+
+/// Exports hold the instance either at their root or in a single folder, so
+/// the instance root is wherever `instance.cfg` sits closest to the top.
+fn find_archive_root(archive: &ZipArchive<File>) -> Result<PathBuf> {
+    archive
+        .file_names()
+        .map(Path::new)
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| name == INSTANCE_CFG_FILE)
+        })
+        .min_by_key(|path| path.components().count())
+        .and_then(Path::parent)
+        .map(Path::to_path_buf)
+        .ok_or(crate::Error::InvalidInstanceImport(format!(
+            "{} not found in archive",
+            INSTANCE_CFG_FILE
+        )))
+}
+
+fn read_archive_instance_files(archive_path: &Path) -> Result<(String, String)> {
+    let mut archive = ZipArchive::new(File::open(archive_path)?)?;
+    let root = find_archive_root(&archive)?;
+
+    let mut read_entry = |file_name: &str| -> Result<String> {
+        let entry_name = match root.to_str() {
+            Some("") | None => file_name.to_string(),
+            Some(root) => format!("{}/{}", root, file_name),
+        };
+        let mut entry = archive.by_name(&entry_name).map_err(|_| {
+            crate::Error::InvalidInstanceImport(format!("{} not found in archive", file_name))
+        })?;
+        let mut content = String::new();
+        entry.read_to_string(&mut content)?;
+        Ok(content)
+    };
+
+    Ok((read_entry(INSTANCE_CFG_FILE)?, read_entry(MMC_PACK_FILE)?))
+}
+
+fn extract_archive_game_dir(archive_path: &Path, target: &Path) -> Result<()> {
+    let mut archive = ZipArchive::new(File::open(archive_path)?)?;
+    let root = find_archive_root(&archive)?;
+
+    let Some(game_dir) = GAME_DIR_NAMES
+        .iter()
+        .map(|name| root.join(name))
+        .find(|game_dir| {
+            archive
+                .file_names()
+                .any(|entry_name| Path::new(entry_name).starts_with(game_dir))
+        })
+    else {
+        return Ok(());
+    };
+
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        // Entries escaping the archive, such as `../`, have no enclosed name.
+        let Some(relative_path) = entry
+            .enclosed_name()
+            .and_then(|path| path.strip_prefix(&game_dir).ok().map(Path::to_path_buf))
+        else {
+            continue;
+        };
+
+        let target_path = target.join(relative_path);
+        if entry.is_dir() {
+            std::fs::create_dir_all(&target_path)?;
+        } else {
+            if let Some(parent) = target_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::io::copy(&mut entry, &mut File::create(&target_path)?)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE_INSTANCE_CFG: &str =
+        include_str!("../../tests/fixtures/prism/forge-instance/instance.cfg");
+    const FIXTURE_MMC_PACK: &str =
+        include_str!("../../tests/fixtures/prism/forge-instance/mmc-pack.json");
+
+    #[test]
+    fn parses_instance_cfg() {
+        let settings = parse_instance_cfg(FIXTURE_INSTANCE_CFG);
+
+        assert_eq!(settings["name"], "Create: Above & Beyond");
+        assert_eq!(
+            settings["JvmArgs"],
+            r#"-XX:+UseG1GC -Dfml.readTimeout="90 s""#
+        );
+        assert_eq!(settings["MaxMemAlloc"], "6144");
+        assert_eq!(settings["notes"], "");
+        assert!(!settings.contains_key("[General]"));
+    }
+
+    #[test]
+    fn parses_forge_instance() {
+        let instance = PrismInstance::parse(FIXTURE_INSTANCE_CFG, FIXTURE_MMC_PACK).unwrap();
+
+        assert_eq!(instance.name.as_deref(), Some("Create: Above & Beyond"));
+        assert_eq!(instance.version_id, "1.20.1");
+        let mod_loader = instance.mod_loader.unwrap();
+        assert_eq!(mod_loader.kind, ModLoaderKind::Forge);
+        assert_eq!(mod_loader.version.as_deref(), Some("1.20.1-47.2.0"));
+        assert_eq!(instance.jvm.min_memory_mb, Some(1024));
+        assert_eq!(instance.jvm.max_memory_mb, Some(6144));
+        assert_eq!(
+            instance.jvm.extra_arguments,
+            vec!["-XX:+UseG1GC", "-Dfml.readTimeout=90 s"]
+        );
+    }
+
+    #[test]
+    fn parses_fabric_instance_without_overrides() {
+        let instance_cfg = "[General]\nOverrideMemory=false\nMaxMemAlloc=4096\n";
+        let mmc_pack = r#"{
+            "components": [
+                { "uid": "net.minecraft", "version": "1.21.1" },
+                { "uid": "net.fabricmc.intermediary", "cachedVersion": "1.21.1" },
+                { "uid": "net.fabricmc.fabric-loader", "cachedVersion": "0.16.5" }
+            ],
+            "formatVersion": 1
+        }"#;
+
+        let instance = PrismInstance::parse(instance_cfg, mmc_pack).unwrap();
+
+        assert_eq!(instance.name, None);
+        assert_eq!(instance.version_id, "1.21.1");
+        let mod_loader = instance.mod_loader.unwrap();
+        assert_eq!(mod_loader.kind, ModLoaderKind::Fabric);
+        assert_eq!(mod_loader.version.as_deref(), Some("0.16.5"));
+        assert_eq!(instance.jvm.max_memory_mb, None);
+        assert!(instance.jvm.extra_arguments.is_empty());
+    }
+
+    #[test]
+    fn rejects_packs_without_minecraft() {
+        let mmc_pack = r#"{ "components": [{ "uid": "org.lwjgl3", "version": "3.3.1" }] }"#;
+
+        assert!(matches!(
+            PrismInstance::parse("", mmc_pack),
+            Err(crate::Error::InvalidInstanceImport(_))
+        ));
+    }
+}
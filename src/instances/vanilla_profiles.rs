@@ -0,0 +1,367 @@
+use std::path::Path;
+
+use crate::{
+    files::{copy_dir_all, create_parent_folders, load_serialized_file, save_serialized_file},
+    jvm_settings::JvmSettings,
+    launch_options::WindowResolution,
+    launcher_paths::join_paths,
+    model::mojang::{
+        LauncherProfile, LauncherProfileResolution, LauncherProfiles, MojangVersionLibrary,
+        MojangVersionManifest, MojangVersionProfile,
+    },
+    LauncherPath, LauncherPaths, Result,
+};
+
+use super::{Instance, InstanceStore, LatestVersion, ModLoader, ModLoaderKind};
+
+const LAUNCHER_PROFILES_FILE: &str = "launcher_profiles.json";
+const LATEST_RELEASE: &str = "latest-release";
+const LATEST_SNAPSHOT: &str = "latest-snapshot";
+const CUSTOM_PROFILE_TYPE: &str = "custom";
+
+impl InstanceStore {
+    /// Turns every profile of the official launcher's `launcher_profiles.json`
+    /// into an instance. Instances keep the game directory of their profile,
+    /// and the versions they launch are copied over from `minecraft_dir` when
+    /// missing here, so Fabric, Quilt and custom versions are reused. Forge
+    /// and NeoForge are installed again, since their installers generate
+    /// libraries no version JSON lists.
+    ///
+    /// Profiles imported before or written by `export_launcher_profiles` are
+    /// recognised by their key and skipped, as are profiles without a version
+    /// to launch.
+    pub async fn import_launcher_profiles(
+        &mut self,
+        minecraft_dir: &str,
+        version_manifest: &MojangVersionManifest,
+    ) -> Result<Vec<Instance>> {
+        let launcher_profiles: LauncherProfiles = load_serialized_file(join_paths(
+            minecraft_dir.to_string(),
+            vec![LAUNCHER_PROFILES_FILE],
+        ))
+        .await?
+        .ok_or(crate::Error::InvalidInstanceImport(format!(
+            "{} not found in {}",
+            LAUNCHER_PROFILES_FILE, minecraft_dir
+        )))?;
+
+        let mut instances = Vec::new();
+        for (profile_key, profile) in launcher_profiles.profiles {
+            if self.find_launcher_profile_instance(&profile_key).is_some() {
+                continue;
+            }
+
+            let Some(version_id) = resolve_profile_version(&profile, version_manifest) else {
+                continue;
+            };
+            self.copy_version_chain(minecraft_dir, &version_id).await?;
+
+            let name = match profile.name.as_deref() {
+                Some(name) if !name.is_empty() => name.to_string(),
+                _ => version_id.clone(),
+            };
+
+            // Mod loader profiles inherit from the Minecraft version they
+            // were installed for.
+            let version_profile: Option<MojangVersionProfile> =
+                load_serialized_file(self.launcher_paths.build_version_file_path(&version_id))
+                    .await?;
+            let mod_loader = version_profile
+                .as_ref()
+                .and_then(|version_profile| version_profile.inherits_from.as_deref())
+                .and_then(|minecraft_version| {
+                    detect_mod_loader(&version_id, minecraft_version)
+                        .map(|mod_loader| (minecraft_version.to_string(), mod_loader))
+                });
+
+            let mut instance = match mod_loader {
+                Some((minecraft_version, mod_loader)) => {
+                    let reinstall = matches!(
+                        mod_loader.kind,
+                        ModLoaderKind::Forge | ModLoaderKind::NeoForge
+                    );
+                    let mut instance = self
+                        .create_instance(name, minecraft_version, Some(mod_loader))
+                        .await?;
+                    if !reinstall {
+                        instance.installed_version_id = Some(version_id);
+                    }
+                    instance
+                }
+                None => self.create_instance(name, version_id, None).await?,
+            };
+
+            instance.launcher_profile_key = Some(profile_key);
+            instance.launcher_profile_latest = profile_latest_version(&profile);
+            instance.game_directory = Some(
+                profile
+                    .game_dir
+                    .unwrap_or_else(|| minecraft_dir.to_string()),
+            );
+            instance.settings.jvm = JvmSettings {
+                java_path: profile.java_dir,
+                ..JvmSettings::from_java_args(profile.java_args.as_deref().unwrap_or_default())
+            };
+            instance.settings.launch_options.resolution =
+                profile.resolution.map(|resolution| WindowResolution {
+                    width: resolution.width,
+                    height: resolution.height,
+                });
+
+            self.save_instance(instance.clone()).await?;
+            instances.push(instance);
+        }
+
+        Ok(instances)
+    }
+
+    /// Writes every instance as a profile of `launcher_profiles.json` in
+    /// `minecraft_dir`, keyed by instance id or by the profile an instance was
+    /// imported from. Other profiles and settings of the file are kept, and
+    /// profiles following the latest release or snapshot keep doing so. The
+    /// official launcher only finds the versions installed in
+    /// `minecraft_dir`, so it is usually the base path of this launcher.
+    pub async fn export_launcher_profiles(&self, minecraft_dir: &str) -> Result<()> {
+        let path = join_paths(minecraft_dir.to_string(), vec![LAUNCHER_PROFILES_FILE]);
+        let mut launcher_profiles: LauncherProfiles = load_serialized_file(path.clone())
+            .await?
+            .unwrap_or_default();
+
+        for instance in &self.instances {
+            let profile_key = instance
+                .launcher_profile_key
+                .clone()
+                .unwrap_or_else(|| instance.id.clone());
+            let profile = launcher_profiles.profiles.entry(profile_key).or_default();
+            let java_args = instance.settings.jvm.to_java_args();
+
+            profile.name = Some(instance.name.clone());
+            match instance.launcher_profile_latest {
+                Some(latest_version) => {
+                    let profile_type = match latest_version {
+                        LatestVersion::Release => LATEST_RELEASE,
+                        LatestVersion::Snapshot => LATEST_SNAPSHOT,
+                    };
+                    profile.r#type = Some(profile_type.to_string());
+                    // The official launcher writes the type here as well.
+                    profile.last_version_id = Some(profile_type.to_string());
+                }
+                None => {
+                    profile.r#type = Some(CUSTOM_PROFILE_TYPE.to_string());
+                    profile.last_version_id = Some(instance.launch_version_id().to_string());
+                }
+            }
+            profile.game_dir = Some(instance.game_directory_path(&self.launcher_paths));
+            profile.java_dir = instance.settings.jvm.java_path.clone();
+            profile.java_args = Some(java_args).filter(|java_args| !java_args.is_empty());
+            profile.resolution = instance
+                .settings
+                .launch_options
+                .resolution
+                .map(|resolution| LauncherProfileResolution {
+                    width: resolution.width,
+                    height: resolution.height,
+                });
+        }
+
+        save_serialized_file(path, launcher_profiles).await
+    }
+
+    /// Exported profiles are keyed by instance id, imported ones keep the key
+    /// of their source profile.
+    fn find_launcher_profile_instance(&self, profile_key: &str) -> Option<&Instance> {
+        self.instances.iter().find(|instance| {
+            instance.launcher_profile_key.as_deref() == Some(profile_key)
+                || (instance.launcher_profile_key.is_none() && instance.id == profile_key)
+        })
+    }
+
+    /// Copies `version_id` and the versions it inherits from out of another
+    /// `.minecraft` folder, with the libraries their JSONs list, when this
+    /// launcher does not have them yet. Versions missing there are left to
+    /// the downloads. Nothing is copied when it is the base path of this
+    /// launcher.
+    async fn copy_version_chain(&self, minecraft_dir: &str, version_id: &str) -> Result<()> {
+        let source_paths = LauncherPaths::new(minecraft_dir.to_string());
+        if is_same_path(&source_paths.base_path, &self.launcher_paths.base_path).await {
+            return Ok(());
+        }
+
+        let mut next_version_id = Some(version_id.to_string());
+        while let Some(version_id) = next_version_id.take() {
+            let version_profile: Option<MojangVersionProfile> =
+                load_serialized_file(source_paths.build_version_file_path(&version_id)).await?;
+            let Some(version_profile) = version_profile else {
+                break;
+            };
+
+            copy_dir_all(
+                &build_version_dir_path(&source_paths, &version_id),
+                &build_version_dir_path(&self.launcher_paths, &version_id),
+                false,
+            )
+            .await?;
+
+            for library_path in version_profile.libraries.iter().flat_map(library_paths) {
+                let source = source_paths.build_library_path(&library_path);
+                let target = self.launcher_paths.build_library_path(&library_path);
+                if Path::new(&source).is_file() && !Path::new(&target).exists() {
+                    create_parent_folders(target.clone()).await?;
+                    tokio::fs::copy(source, target).await?;
+                }
+            }
+
+            next_version_id = version_profile.inherits_from;
+        }
+
+        Ok(())
+    }
+}
+
+fn build_version_dir_path(launcher_paths: &LauncherPaths, version_id: &str) -> String {
+    join_paths(
+        launcher_paths.get_path(LauncherPath::VersionsDir),
+        vec![version_id],
+    )
+}
+
+/// Paths of the artifact and native classifiers of a library inside
+/// `libraries/`.
+fn library_paths(library: &MojangVersionLibrary) -> Vec<String> {
+    let downloads = library.downloads.as_ref();
+    let artifact_path = downloads
+        .and_then(|downloads| downloads.artifact.as_ref())
+        .and_then(|artifact| artifact.path.clone())
+        .or_else(|| {
+            library
+                .maven_artifact()
+                .ok()
+                .map(|artifact| artifact.path())
+        });
+    let classifier_paths = downloads
+        .and_then(|downloads| downloads.classifiers.as_ref())
+        .into_iter()
+        .flat_map(|classifiers| {
+            classifiers
+                .values()
+                .map(|classifier| classifier.path.clone())
+        });
+
+    artifact_path.into_iter().chain(classifier_paths).collect()
+}
+
+/// Only the `latest-release` and `latest-snapshot` profiles may leave out
+/// `lastVersionId`; a custom profile without one has nothing to launch.
+fn resolve_profile_version(
+    profile: &LauncherProfile,
+    version_manifest: &MojangVersionManifest,
+) -> Option<String> {
+    Some(match profile_latest_version(profile) {
+        Some(LatestVersion::Release) => version_manifest.latest.release.clone(),
+        Some(LatestVersion::Snapshot) => version_manifest.latest.snapshot.clone(),
+        None => profile.last_version_id.clone()?,
+    })
+}
+
+/// `latest-release` or `latest-snapshot`, given as `lastVersionId` or, when
+/// that is missing, as the profile type.
+fn profile_latest_version(profile: &LauncherProfile) -> Option<LatestVersion> {
+    match profile
+        .last_version_id
+        .as_deref()
+        .or(profile.r#type.as_deref())?
+    {
+        LATEST_RELEASE => Some(LatestVersion::Release),
+        LATEST_SNAPSHOT => Some(LatestVersion::Snapshot),
+        _ => None,
+    }
+}
+
+async fn is_same_path(a: &str, b: &str) -> bool {
+    match (
+        tokio::fs::canonicalize(a).await,
+        tokio::fs::canonicalize(b).await,
+    ) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Recognises the version ids written by the mod loader installers, such as
+/// `fabric-loader-0.16.9-1.21.1` or `1.20.1-forge-47.2.0`.
+fn detect_mod_loader(version_id: &str, minecraft_version: &str) -> Option<ModLoader> {
+    let loader_suffix = format!("-{}", minecraft_version);
+
+    let (kind, version) = if let Some(rest) = version_id.strip_prefix("fabric-loader-") {
+        (ModLoaderKind::Fabric, rest.strip_suffix(&loader_suffix))
+    } else if let Some(rest) = version_id.strip_prefix("quilt-loader-") {
+        (ModLoaderKind::Quilt, rest.strip_suffix(&loader_suffix))
+    } else if let Some(version) = version_id.strip_prefix("neoforge-") {
+        (ModLoaderKind::NeoForge, Some(version))
+    } else if let Some(build) = version_id.strip_prefix(&format!("{}-forge-", minecraft_version)) {
+        return Some(ModLoader {
+            kind: ModLoaderKind::Forge,
+            version: Some(format!("{}-{}", minecraft_version, build)),
+        });
+    } else {
+        return None;
+    };
+
+    Some(ModLoader {
+        kind,
+        version: version.map(str::to_string),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::mojang::MojangVersionManifestLatest;
+
+    fn profile(profile_type: Option<&str>, last_version_id: Option<&str>) -> LauncherProfile {
+        LauncherProfile {
+            r#type: profile_type.map(str::to_string),
+            last_version_id: last_version_id.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn resolves_profile_versions() {
+        let version_manifest = MojangVersionManifest {
+            latest: MojangVersionManifestLatest {
+                release: "1.21.1".to_string(),
+                snapshot: "24w40a".to_string(),
+            },
+            versions: Vec::new(),
+        };
+        let cases = [
+            (Some("latest-release"), None, Some("1.21.1")),
+            (Some("latest-snapshot"), None, Some("24w40a")),
+            (
+                Some("latest-release"),
+                Some("latest-release"),
+                Some("1.21.1"),
+            ),
+            (Some("custom"), Some("1.20.1"), Some("1.20.1")),
+            (
+                None,
+                Some("fabric-loader-0.16.5-1.21.1"),
+                Some("fabric-loader-0.16.5-1.21.1"),
+            ),
+            (Some("custom"), None, None),
+            (None, None, None),
+        ];
+
+        for (profile_type, last_version_id, expected) in cases {
+            assert_eq!(
+                resolve_profile_version(&profile(profile_type, last_version_id), &version_manifest)
+                    .as_deref(),
+                expected,
+                "{:?} {:?}",
+                profile_type,
+                last_version_id
+            );
+        }
+    }
+}
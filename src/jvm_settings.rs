@@ -0,0 +1,268 @@
+use std::{collections::BTreeMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Result;
+
+/// JVM options set globally in the launcher config and per instance. Fields
+/// left empty on an instance fall back to the global value.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct JvmSettings {
+    /// Initial heap size in megabytes, passed as `-Xms`.
+    pub min_memory_mb: Option<u32>,
+    /// Maximum heap size in megabytes, passed as `-Xmx`.
+    pub max_memory_mb: Option<u32>,
+    /// Arguments such as GC flags or system properties, added after the
+    /// arguments of the version so they take precedence.
+    #[serde(default)]
+    pub extra_arguments: Vec<String>,
+    #[serde(default)]
+    pub environment: BTreeMap<String, String>,
+    /// Java binary used instead of the runtime managed by the launcher.
+    pub java_path: Option<String>,
+}
+
+impl JvmSettings {
+    /// Layers these settings over `defaults`: single values replace the
+    /// default ones, extra arguments come after the default ones and
+    /// environment variables override those with the same name.
+    pub fn merged_with(&self, defaults: &JvmSettings) -> JvmSettings {
+        let mut environment = defaults.environment.clone();
+        environment.extend(self.environment.clone());
+
+        JvmSettings {
+            min_memory_mb: self.min_memory_mb.or(defaults.min_memory_mb),
+            max_memory_mb: self.max_memory_mb.or(defaults.max_memory_mb),
+            extra_arguments: defaults
+                .extra_arguments
+                .iter()
+                .chain(&self.extra_arguments)
+                .cloned()
+                .collect(),
+            environment,
+            java_path: self.java_path.clone().or(defaults.java_path.clone()),
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: String| Err(crate::Error::InvalidJvmSettings(reason));
+
+        if self.min_memory_mb == Some(0) || self.max_memory_mb == Some(0) {
+            return invalid("memory must be greater than zero".to_string());
+        }
+
+        if let (Some(min), Some(max)) = (self.min_memory_mb, self.max_memory_mb) {
+            if min > max {
+                return invalid(format!(
+                    "minimum memory of {} MB is larger than the maximum of {} MB",
+                    min, max
+                ));
+            }
+        }
+
+        if let Some(name) = self
+            .environment
+            .keys()
+            .find(|name| name.is_empty() || name.contains('=') || name.contains('\0'))
+        {
+            return invalid(format!("invalid environment variable name {:?}", name));
+        }
+
+        // A bare name such as `java` is looked up on PATH when the game starts.
+        if let Some(java_path) = &self.java_path {
+            let path = Path::new(java_path);
+            if path.components().count() > 1 && !path.is_file() {
+                return invalid(format!("java executable {} does not exist", java_path));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads a single string of JVM arguments, as stored by other launchers,
+    /// moving `-Xms` and `-Xmx` into the memory settings.
+    pub fn from_java_args(java_args: &str) -> JvmSettings {
+        let mut jvm_settings = JvmSettings::default();
+
+        for argument in split_jvm_arguments(java_args) {
+            if let Some(memory_mb) = argument.strip_prefix("-Xms").and_then(parse_memory_mb) {
+                jvm_settings.min_memory_mb = Some(memory_mb);
+            } else if let Some(memory_mb) = argument.strip_prefix("-Xmx").and_then(parse_memory_mb)
+            {
+                jvm_settings.max_memory_mb = Some(memory_mb);
+            } else {
+                jvm_settings.extra_arguments.push(argument);
+            }
+        }
+
+        jvm_settings
+    }
+
+    /// The memory and extra arguments as a single string, the reverse of
+    /// `from_java_args`.
+    pub fn to_java_args(&self) -> String {
+        self.memory_arguments()
+            .iter()
+            .chain(&self.extra_arguments)
+            .map(|argument| {
+                if argument.contains(char::is_whitespace) {
+                    format!("\"{}\"", argument)
+                } else {
+                    argument.clone()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub fn memory_arguments(&self) -> Vec<String> {
+        let mut arguments = Vec::new();
+
+        if let Some(min_memory_mb) = self.min_memory_mb {
+            arguments.push(format!("-Xms{}M", min_memory_mb));
+        }
+        if let Some(max_memory_mb) = self.max_memory_mb {
+            arguments.push(format!("-Xmx{}M", max_memory_mb));
+        }
+
+        arguments
+    }
+}
+
+/// Splits JVM arguments on whitespace, keeping double quoted parts together.
+pub fn split_jvm_arguments(value: &str) -> Vec<String> {
+    let mut arguments = Vec::new();
+    let mut current = None::<String>;
+    let mut quoted = false;
+
+    for c in value.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                current.get_or_insert_with(String::new);
+            }
+            c if c.is_whitespace() && !quoted => arguments.extend(current.take()),
+            c => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    arguments.extend(current);
+
+    arguments
+}
+
+/// Parses the size of `-Xms`/`-Xmx`, such as `2G`, `512M` or `524288k`.
+fn parse_memory_mb(size: &str) -> Option<u32> {
+    let (number, bytes_per_unit) = match size.chars().last()?.to_ascii_lowercase() {
+        'g' => (&size[..size.len() - 1], 1024 * 1024 * 1024),
+        'm' => (&size[..size.len() - 1], 1024 * 1024),
+        'k' => (&size[..size.len() - 1], 1024),
+        _ => (size, 1),
+    };
+    let memory_mb = number.parse::<u64>().ok()?.checked_mul(bytes_per_unit)? / (1024 * 1024);
+
+    u32::try_from(memory_mb)
+        .ok()
+        .filter(|memory_mb| *memory_mb > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_memory_sizes() {
+        let cases = [
+            ("2G", Some(2048)),
+            ("2g", Some(2048)),
+            ("512M", Some(512)),
+            ("524288k", Some(512)),
+            ("1073741824", Some(1024)),
+            ("100k", None),
+            ("0G", None),
+            ("-1G", None),
+            ("1.5G", None),
+            ("G", None),
+            ("", None),
+            ("lots", None),
+            ("99999999999999G", None),
+        ];
+
+        for (size, expected) in cases {
+            assert_eq!(parse_memory_mb(size), expected, "{}", size);
+        }
+    }
+
+    #[test]
+    fn splits_quoted_arguments() {
+        assert_eq!(
+            split_jvm_arguments(r#"  -Xss2M -Dname="a b"   "-Dpath=C:\My Games" "#),
+            vec!["-Xss2M", "-Dname=a b", r"-Dpath=C:\My Games"]
+        );
+        assert_eq!(split_jvm_arguments(r#"-Da="" """#), vec!["-Da=", ""]);
+        assert!(split_jvm_arguments(" \t ").is_empty());
+    }
+
+    #[test]
+    fn validates_settings() {
+        let valid = |jvm_settings: JvmSettings| jvm_settings.validate().is_ok();
+
+        assert!(valid(JvmSettings::default()));
+        assert!(valid(JvmSettings {
+            min_memory_mb: Some(512),
+            max_memory_mb: Some(4096),
+            ..Default::default()
+        }));
+        assert!(!valid(JvmSettings {
+            min_memory_mb: Some(4096),
+            max_memory_mb: Some(512),
+            ..Default::default()
+        }));
+        assert!(!valid(JvmSettings {
+            max_memory_mb: Some(0),
+            ..Default::default()
+        }));
+        assert!(!valid(JvmSettings {
+            environment: BTreeMap::from([("A=B".to_string(), "1".to_string())]),
+            ..Default::default()
+        }));
+
+        let java_path = |java_path: &str| JvmSettings {
+            java_path: Some(java_path.to_string()),
+            ..Default::default()
+        };
+        assert!(valid(java_path("java")));
+        assert!(!valid(java_path("/nonexistent/bin/java")));
+        assert!(!valid(java_path("./nonexistent-java")));
+        let existing_file = std::env::current_exe().unwrap();
+        assert!(valid(java_path(&existing_file.to_string_lossy())));
+    }
+
+    #[test]
+    fn merges_extra_arguments_after_defaults() {
+        let defaults = JvmSettings {
+            max_memory_mb: Some(2048),
+            extra_arguments: vec!["-XX:+UseG1GC".to_string(), "-Dfoo=default".to_string()],
+            environment: BTreeMap::from([
+                ("A".to_string(), "default".to_string()),
+                ("B".to_string(), "default".to_string()),
+            ]),
+            ..Default::default()
+        };
+        let instance_settings = JvmSettings {
+            max_memory_mb: Some(4096),
+            extra_arguments: vec!["-Dfoo=instance".to_string()],
+            environment: BTreeMap::from([("B".to_string(), "instance".to_string())]),
+            ..Default::default()
+        };
+
+        let merged = instance_settings.merged_with(&defaults);
+
+        assert_eq!(merged.max_memory_mb, Some(4096));
+        assert_eq!(
+            merged.extra_arguments,
+            vec!["-XX:+UseG1GC", "-Dfoo=default", "-Dfoo=instance"]
+        );
+        assert_eq!(merged.environment["A"], "default");
+        assert_eq!(merged.environment["B"], "instance");
+    }
+}
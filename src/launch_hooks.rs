@@ -0,0 +1,293 @@
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+use tokio_util::sync::CancellationToken;
+
+use crate::{instances::Instance, launcher_runner::force_termination, Result};
+
+/// Commands run around the game, set globally in the launcher config and per
+/// instance. Each command is a program followed by its arguments, run
+/// without a shell; use `["sh", "-c", "..."]` for shell syntax.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LaunchHooks {
+    /// Prepended to the game command, e.g. `["gamemoderun"]`. Cancelling
+    /// the launch signals the wrapper together with the game it started.
+    pub wrapper_command: Option<Vec<String>>,
+    /// Runs before the game starts; a failure aborts the launch.
+    pub pre_launch_command: Option<Vec<String>>,
+    /// Runs after the game exited, also when it crashed or was cancelled.
+    pub post_exit_command: Option<Vec<String>>,
+}
+
+/// What a hook is told about the game through its environment.
+pub struct HookEnvironment<'a> {
+    pub instance: &'a Instance,
+    pub version_id: &'a str,
+    pub game_directory: &'a str,
+    /// Only known to the post-exit hook, and not when the game was killed
+    /// by a signal.
+    pub exit_code: Option<i32>,
+}
+
+impl LaunchHooks {
+    /// Commands set on the instance replace the default ones; an empty
+    /// command disables the default.
+    pub fn merged_with(&self, defaults: &LaunchHooks) -> LaunchHooks {
+        LaunchHooks {
+            wrapper_command: self
+                .wrapper_command
+                .clone()
+                .or(defaults.wrapper_command.clone()),
+            pre_launch_command: self
+                .pre_launch_command
+                .clone()
+                .or(defaults.pre_launch_command.clone()),
+            post_exit_command: self
+                .post_exit_command
+                .clone()
+                .or(defaults.post_exit_command.clone()),
+        }
+    }
+
+    pub fn wrap_command(&self, command: Vec<String>) -> Vec<String> {
+        match &self.wrapper_command {
+            Some(wrapper_command) => wrapper_command.iter().cloned().chain(command).collect(),
+            None => command,
+        }
+    }
+
+    pub async fn run_pre_launch(
+        &self,
+        environment: &HookEnvironment<'_>,
+        cancellation: &CancellationToken,
+    ) -> Result<()> {
+        let Some(command) = &self.pre_launch_command else {
+            return Ok(());
+        };
+
+        run_hook(command, environment, cancellation).await
+    }
+
+    /// Runs even after a cancelled launch, so it is not cancellable itself.
+    pub async fn run_post_exit(&self, environment: &HookEnvironment<'_>) -> Result<()> {
+        let Some(command) = &self.post_exit_command else {
+            return Ok(());
+        };
+
+        run_hook(command, environment, &CancellationToken::new()).await
+    }
+}
+
+impl HookEnvironment<'_> {
+    pub fn variables(&self) -> Vec<(&'static str, String)> {
+        let mut variables = vec![
+            ("INST_ID", self.instance.id.clone()),
+            ("INST_NAME", self.instance.name.clone()),
+            ("INST_MC_VERSION", self.instance.version_id.clone()),
+            ("INST_VERSION", self.version_id.to_string()),
+            ("INST_MC_DIR", self.game_directory.to_string()),
+        ];
+        if let Some(exit_code) = self.exit_code {
+            variables.push(("INST_EXIT_CODE", exit_code.to_string()));
+        }
+
+        variables
+    }
+}
+
+async fn run_hook(
+    command: &[String],
+    environment: &HookEnvironment<'_>,
+    cancellation: &CancellationToken,
+) -> Result<()> {
+    let Some((program, arguments)) = command.split_first() else {
+        return Ok(());
+    };
+
+    let mut command_builder = Command::new(program);
+    command_builder
+        .args(arguments)
+        .envs(environment.variables())
+        .current_dir(environment.game_directory)
+        .kill_on_drop(true);
+
+    // Like the game, so cancelling also kills what a `sh -c` hook started.
+    #[cfg(unix)]
+    command_builder.process_group(0);
+
+    let mut child = command_builder.spawn()?;
+    let pid = child.id();
+
+    let status = tokio::select! {
+        status = child.wait() => status?,
+        _ = cancellation.cancelled() => {
+            force_termination(pid, &mut child).await?;
+            return Err(crate::Error::Cancelled);
+        }
+    };
+
+    if !status.success() {
+        return Err(crate::Error::HookFailed {
+            command: command.join(" "),
+            exit_code: status.code(),
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instances::InstanceSettings;
+
+    fn command(parts: &[&str]) -> Option<Vec<String>> {
+        Some(parts.iter().map(|part| part.to_string()).collect())
+    }
+
+    fn test_instance() -> Instance {
+        Instance {
+            id: "c0ffee".to_string(),
+            name: "Survival".to_string(),
+            version_id: "1.20.1".to_string(),
+            mod_loader: None,
+            settings: InstanceSettings::default(),
+            installed_version_id: None,
+            game_directory: None,
+            launcher_profile_key: None,
+            launcher_profile_latest: None,
+        }
+    }
+
+    fn test_game_directory(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!(
+            "minecraft-launcher-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn instance_hooks_override_defaults() {
+        let defaults = LaunchHooks {
+            wrapper_command: command(&["gamemoderun"]),
+            pre_launch_command: command(&["sync-mods"]),
+            post_exit_command: command(&["backup-saves"]),
+        };
+        let instance_hooks = LaunchHooks {
+            wrapper_command: Some(Vec::new()),
+            pre_launch_command: command(&["sync-mods", "--fast"]),
+            post_exit_command: None,
+        };
+
+        let hooks = instance_hooks.merged_with(&defaults);
+
+        assert_eq!(hooks.wrapper_command, Some(Vec::new()));
+        assert_eq!(hooks.pre_launch_command, command(&["sync-mods", "--fast"]));
+        assert_eq!(hooks.post_exit_command, command(&["backup-saves"]));
+        assert_eq!(
+            hooks.wrap_command(vec!["java".to_string()]),
+            vec!["java".to_string()]
+        );
+    }
+
+    #[test]
+    fn wraps_game_command() {
+        let hooks = LaunchHooks {
+            wrapper_command: command(&["prime-run", "gamemoderun"]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            hooks.wrap_command(vec!["java".to_string(), "-jar".to_string()]),
+            vec!["prime-run", "gamemoderun", "java", "-jar"]
+        );
+        assert_eq!(
+            LaunchHooks::default().wrap_command(vec!["java".to_string()]),
+            vec!["java"]
+        );
+    }
+
+    #[test]
+    fn exposes_instance_variables() {
+        let instance = test_instance();
+        let environment = HookEnvironment {
+            instance: &instance,
+            version_id: "1.20.1-forge-47.2.0",
+            game_directory: "/games/survival",
+            exit_code: Some(1),
+        };
+
+        assert_eq!(
+            environment.variables(),
+            vec![
+                ("INST_ID", "c0ffee".to_string()),
+                ("INST_NAME", "Survival".to_string()),
+                ("INST_MC_VERSION", "1.20.1".to_string()),
+                ("INST_VERSION", "1.20.1-forge-47.2.0".to_string()),
+                ("INST_MC_DIR", "/games/survival".to_string()),
+                ("INST_EXIT_CODE", "1".to_string()),
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn failing_pre_launch_hook_aborts_launch() {
+        let instance = test_instance();
+        let game_directory = test_game_directory("pre-launch-hook");
+        let environment = HookEnvironment {
+            instance: &instance,
+            version_id: "1.20.1",
+            game_directory: &game_directory,
+            exit_code: None,
+        };
+        let hooks = LaunchHooks {
+            pre_launch_command: command(&["sh", "-c", "exit 3"]),
+            ..Default::default()
+        };
+
+        let result = hooks
+            .run_pre_launch(&environment, &CancellationToken::new())
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(crate::Error::HookFailed {
+                exit_code: Some(3),
+                ..
+            })
+        ));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn passes_variables_to_post_exit_hook() {
+        let instance = test_instance();
+        let game_directory = test_game_directory("post-exit-hook");
+        let environment = HookEnvironment {
+            instance: &instance,
+            version_id: "1.20.1",
+            game_directory: &game_directory,
+            exit_code: Some(0),
+        };
+        let hooks = LaunchHooks {
+            post_exit_command: command(&[
+                "sh",
+                "-c",
+                "echo \"$INST_NAME $INST_EXIT_CODE\" > hook.txt",
+            ]),
+            ..Default::default()
+        };
+
+        hooks.run_post_exit(&environment).await.unwrap();
+
+        let output =
+            std::fs::read_to_string(std::path::Path::new(&game_directory).join("hook.txt"))
+                .unwrap();
+        assert_eq!(output.trim(), "Survival 0");
+    }
+}
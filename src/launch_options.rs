@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    launch_placeholders::LaunchPlaceholders, launcher_paths::join_paths,
+    model::mojang::RuleContext, platform::PlatformData,
+};
+
+/// Options of a launch, mapped to the `features` that rule-based arguments
+/// of the version JSON depend on. Instances save defaults, such as the
+/// resolution, and each launch can add its own on top.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LaunchOptions {
+    pub resolution: Option<WindowResolution>,
+    pub demo: bool,
+    pub quick_play: Option<QuickPlayTarget>,
+    /// Fails the launch on placeholders the launcher does not know about,
+    /// so new additions to the version JSON get noticed.
+    pub strict_placeholders: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct WindowResolution {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Where the game goes right after starting, skipping the title screen.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum QuickPlayTarget {
+    Singleplayer(String),
+    Multiplayer(String),
+    Realms(String),
+}
+
+impl LaunchOptions {
+    /// Options of this launch over the saved `defaults`; flags are enabled
+    /// when either enables them.
+    pub fn merged_with(&self, defaults: &LaunchOptions) -> LaunchOptions {
+        LaunchOptions {
+            resolution: self.resolution.or(defaults.resolution),
+            demo: self.demo || defaults.demo,
+            quick_play: self.quick_play.clone().or(defaults.quick_play.clone()),
+            strict_placeholders: self.strict_placeholders || defaults.strict_placeholders,
+        }
+    }
+
+    pub fn rule_context<'a>(&self, platform_data: &'a PlatformData) -> RuleContext<'a> {
+        let quick_play = self.quick_play.as_ref();
+
+        RuleContext::new(platform_data)
+            .with_feature("has_custom_resolution", self.resolution.is_some())
+            .with_feature("is_demo_user", self.demo)
+            .with_feature("has_quick_plays_support", quick_play.is_some())
+            .with_feature(
+                "is_quick_play_singleplayer",
+                matches!(quick_play, Some(QuickPlayTarget::Singleplayer(_))),
+            )
+            .with_feature(
+                "is_quick_play_multiplayer",
+                matches!(quick_play, Some(QuickPlayTarget::Multiplayer(_))),
+            )
+            .with_feature(
+                "is_quick_play_realms",
+                matches!(quick_play, Some(QuickPlayTarget::Realms(_))),
+            )
+    }
+
+    /// Fills the placeholders that only exist when a feature is enabled.
+    pub fn insert_placeholders(&self, placeholders: &mut LaunchPlaceholders, game_directory: &str) {
+        if let Some(resolution) = &self.resolution {
+            placeholders.insert("resolution_width", resolution.width.to_string());
+            placeholders.insert("resolution_height", resolution.height.to_string());
+        }
+
+        if let Some(quick_play) = &self.quick_play {
+            placeholders.insert(
+                "quickPlayPath",
+                join_paths(
+                    game_directory.to_string(),
+                    vec!["quickPlay", "java", "quick_play.json"],
+                ),
+            );
+
+            let (placeholder, value) = match quick_play {
+                QuickPlayTarget::Singleplayer(world) => ("quickPlaySingleplayer", world),
+                QuickPlayTarget::Multiplayer(address) => ("quickPlayMultiplayer", address),
+                QuickPlayTarget::Realms(realm_id) => ("quickPlayRealms", realm_id),
+            };
+            placeholders.insert(placeholder, value.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::{Arch, PlatformType};
+
+    #[test]
+    fn launch_options_override_saved_defaults() {
+        let defaults = LaunchOptions {
+            resolution: Some(WindowResolution {
+                width: 1280,
+                height: 720,
+            }),
+            ..Default::default()
+        };
+        let launch_options = LaunchOptions {
+            quick_play: Some(QuickPlayTarget::Multiplayer("mc.example.com".to_string())),
+            ..Default::default()
+        };
+
+        let merged = launch_options.merged_with(&defaults);
+        assert_eq!(
+            merged.resolution.map(|resolution| resolution.width),
+            Some(1280)
+        );
+        assert!(matches!(
+            merged.quick_play,
+            Some(QuickPlayTarget::Multiplayer(address)) if address == "mc.example.com"
+        ));
+
+        // Nothing of a single launch is kept for the next one.
+        let next_launch = LaunchOptions::default().merged_with(&defaults);
+        assert!(next_launch.quick_play.is_none());
+        assert!(!next_launch.demo);
+    }
+
+    #[test]
+    fn quick_play_targets_enable_their_features_and_placeholders() {
+        let platform_data = PlatformData {
+            platform_type: PlatformType::Linux,
+            arch: Arch::X86_64,
+            os_version: String::new(),
+        };
+        let cases = [
+            (
+                QuickPlayTarget::Singleplayer("New World".to_string()),
+                "is_quick_play_singleplayer",
+                "quickPlaySingleplayer",
+                "New World",
+            ),
+            (
+                QuickPlayTarget::Multiplayer("mc.example.com:25565".to_string()),
+                "is_quick_play_multiplayer",
+                "quickPlayMultiplayer",
+                "mc.example.com:25565",
+            ),
+            (
+                QuickPlayTarget::Realms("1234567".to_string()),
+                "is_quick_play_realms",
+                "quickPlayRealms",
+                "1234567",
+            ),
+        ];
+        let target_features = [
+            "is_quick_play_singleplayer",
+            "is_quick_play_multiplayer",
+            "is_quick_play_realms",
+        ];
+        let target_placeholders = [
+            "quickPlaySingleplayer",
+            "quickPlayMultiplayer",
+            "quickPlayRealms",
+        ];
+
+        for (quick_play, feature, placeholder, value) in cases {
+            let launch_options = LaunchOptions {
+                quick_play: Some(quick_play),
+                ..Default::default()
+            };
+
+            let features = launch_options.rule_context(&platform_data).features;
+            assert!(features["has_quick_plays_support"], "{}", feature);
+            for target_feature in target_features {
+                assert_eq!(
+                    features[target_feature],
+                    target_feature == feature,
+                    "{}",
+                    target_feature
+                );
+            }
+
+            let mut placeholders = LaunchPlaceholders::default();
+            launch_options.insert_placeholders(&mut placeholders, "/games/survival");
+            assert_eq!(
+                placeholders.get("quickPlayPath"),
+                Some(
+                    join_paths(
+                        "/games/survival".to_string(),
+                        vec!["quickPlay", "java", "quick_play.json"],
+                    )
+                    .as_str()
+                )
+            );
+            for target_placeholder in target_placeholders {
+                let expected = (target_placeholder == placeholder).then_some(value);
+                assert_eq!(
+                    placeholders.get(target_placeholder),
+                    expected,
+                    "{}",
+                    target_placeholder
+                );
+            }
+        }
+
+        let features = LaunchOptions::default()
+            .rule_context(&platform_data)
+            .features;
+        assert!(!features["has_quick_plays_support"]);
+    }
+}
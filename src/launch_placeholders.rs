@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use crate::Result;
+
+/// Values of the `${...}` tokens found in JVM and game arguments.
+#[derive(Debug, Clone, Default)]
+pub struct LaunchPlaceholders {
+    values: HashMap<String, String>,
+}
+
+impl LaunchPlaceholders {
+    pub fn insert(&mut self, name: &str, value: impl Into<String>) {
+        self.values.insert(name.to_string(), value.into());
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    /// Replaces every placeholder of the argument. Unknown placeholders are
+    /// kept as they are, or rejected when `strict` is set.
+    pub fn substitute(&self, argument: &str, strict: bool) -> Result<String> {
+        let mut substituted = String::with_capacity(argument.len());
+        let mut rest = argument;
+
+        while let Some(start) = rest.find("${") {
+            substituted.push_str(&rest[..start]);
+
+            let placeholder = &rest[start + 2..];
+            let end = match placeholder.find('}') {
+                Some(end) => end,
+                None => {
+                    substituted.push_str(&rest[start..]);
+                    return Ok(substituted);
+                }
+            };
+
+            let name = &placeholder[..end];
+            match self.values.get(name) {
+                Some(value) => substituted.push_str(value),
+                None if strict => {
+                    return Err(crate::Error::UnknownLaunchPlaceholder(name.to_string()))
+                }
+                None => substituted.push_str(&rest[start..start + end + 3]),
+            }
+
+            rest = &placeholder[end + 1..];
+        }
+
+        substituted.push_str(rest);
+        Ok(substituted)
+    }
+
+    pub fn substitute_all(&self, arguments: &[String], strict: bool) -> Result<Vec<String>> {
+        arguments
+            .iter()
+            .map(|argument| self.substitute(argument, strict))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders() -> LaunchPlaceholders {
+        let mut placeholders = LaunchPlaceholders::default();
+        placeholders.insert("auth_player_name", "Notch");
+        placeholders.insert("version_name", "1.21.1");
+        placeholders.insert("clientid", "abc123");
+        placeholders
+    }
+
+    #[test]
+    fn substitutes_every_placeholder() {
+        let substituted = placeholders()
+            .substitute("--name=${auth_player_name}@${version_name}", false)
+            .unwrap();
+
+        assert_eq!(substituted, "--name=Notch@1.21.1");
+    }
+
+    #[test]
+    fn substitutes_in_one_pass() {
+        let mut placeholders = placeholders();
+        placeholders.insert("auth_player_name", "${clientid}");
+
+        let substituted = placeholders
+            .substitute("${auth_player_name}", false)
+            .unwrap();
+
+        assert_eq!(substituted, "${clientid}");
+    }
+
+    #[test]
+    fn keeps_unknown_and_unclosed_placeholders() {
+        let placeholders = placeholders();
+
+        assert_eq!(
+            placeholders
+                .substitute("${unknown}-${clientid}", false)
+                .unwrap(),
+            "${unknown}-abc123"
+        );
+        assert_eq!(
+            placeholders
+                .substitute("${clientid}-${broken", false)
+                .unwrap(),
+            "abc123-${broken"
+        );
+    }
+
+    #[test]
+    fn strict_mode_rejects_unknown_placeholders() {
+        let result = placeholders().substitute("${unknown}", true);
+
+        assert!(
+            matches!(result, Err(crate::Error::UnknownLaunchPlaceholder(name)) if name == "unknown")
+        );
+    }
+
+    #[test]
+    fn substitutes_all_arguments() {
+        let arguments = vec!["--clientId".to_string(), "${clientid}".to_string()];
+
+        assert_eq!(
+            placeholders().substitute_all(&arguments, true).unwrap(),
+            vec!["--clientId", "abc123"]
+        );
+    }
+}
@@ -1,10 +1,21 @@
+use tokio_util::sync::CancellationToken;
+
 use crate::{
+    accounts::AccountStore,
+    auth::{AuthSession, MicrosoftAuthenticator},
     config::LauncherConfig,
-    files::load_file_or_download_serialized,
-    launcher_runner::launch_game,
+    download_plan::DownloadContext,
+    download_progress::DownloadProgress,
+    files::{load_file_or_download_serialized, load_serialized_file},
+    game_exit::GameExit,
+    game_log::{ConsoleLogSink, GameLogSink},
+    instances::{Instance, InstanceStore, ModLoaderKind},
+    launch_options::LaunchOptions,
+    launcher_runner::{launch_game, LaunchContext},
     launcher_version::LauncherVersion,
+    loaders::{FabricInstaller, ForgeInstaller},
     model::{
-        mojang::{MojangVersionManifest, MojangVersionManifestVersion},
+        mojang::{MojangVersionManifest, MojangVersionManifestVersion, MojangVersionProfile},
         VersionType,
     },
     platform::PlatformData,
@@ -21,6 +32,10 @@ pub struct Launcher {
     pub version_manifest: Option<MojangVersionManifest>,
     pub selected_manifest_version: Option<MojangVersionManifestVersion>,
     pub selected_launcher_version: Option<LauncherVersion>,
+    pub account_store: AccountStore,
+    pub instance_store: InstanceStore,
+    pub authenticator: Option<MicrosoftAuthenticator>,
+    pub log_sink: Box<dyn GameLogSink>,
 }
 
 impl Launcher {
@@ -28,6 +43,8 @@ impl Launcher {
         platform_data: PlatformData,
         launcher_paths: LauncherPaths,
         config: LauncherConfig,
+        account_store: AccountStore,
+        instance_store: InstanceStore,
     ) -> Launcher {
         Launcher {
             platform_data,
@@ -36,6 +53,10 @@ impl Launcher {
             version_manifest: None,
             selected_manifest_version: None,
             selected_launcher_version: None,
+            account_store,
+            instance_store,
+            authenticator: None,
+            log_sink: Box::new(ConsoleLogSink),
         }
     }
 
@@ -44,7 +65,7 @@ impl Launcher {
         let download_url = VERSION_MANIFEST_V2_URL.to_string();
 
         let manifest_file: MojangVersionManifest =
-            load_file_or_download_serialized(path, download_url).await?;
+            load_file_or_download_serialized(path, download_url, None, None).await?;
 
         self.version_manifest = Some(manifest_file.clone());
 
@@ -65,11 +86,21 @@ impl Launcher {
         Ok(filtered_versions)
     }
 
-    pub fn select_version(&mut self, version_id: String) -> Result<MojangVersionManifestVersion> {
-        let version = self
+    pub async fn select_version(
+        &mut self,
+        version_id: String,
+    ) -> Result<MojangVersionManifestVersion> {
+        let manifest_version = self
             .version_manifest_or_err()?
-            .find_version_by_id(version_id.clone())
-            .ok_or(crate::Error::VersionNotFound(version_id.clone()))?;
+            .find_version_by_id(version_id.clone());
+
+        let version = match manifest_version {
+            Some(version) => version,
+            None => self
+                .find_installed_version(&version_id)
+                .await?
+                .ok_or(crate::Error::VersionNotFound(version_id.clone()))?,
+        };
 
         self.selected_manifest_version = Some(version.clone());
         self.config.last_selected_version_id = Some(version_id.clone());
@@ -77,11 +108,60 @@ impl Launcher {
         Ok(version)
     }
 
+    pub fn set_authenticator(&mut self, authenticator: MicrosoftAuthenticator) {
+        self.authenticator = Some(authenticator);
+    }
+
+    pub fn set_log_sink(&mut self, log_sink: impl GameLogSink + 'static) {
+        self.log_sink = Box::new(log_sink);
+    }
+
+    /// Runs a Forge or NeoForge installer and returns the id of the installed
+    /// version, which can then be passed to `select_version`.
+    pub async fn install_forge(
+        &self,
+        installer: &ForgeInstaller,
+        loader_version: &str,
+        progress: &dyn DownloadProgress,
+        cancellation: &CancellationToken,
+    ) -> Result<String> {
+        installer
+            .install(
+                &self.launcher_paths,
+                &self.platform_data,
+                self.version_manifest_or_err()?,
+                loader_version,
+                self.download_context(progress, cancellation),
+            )
+            .await
+    }
+
+    /// Looks for a version JSON under `versions/` that is not listed in the
+    /// Mojang manifest, such as the profiles written by mod loader installers.
+    pub async fn find_installed_version(
+        &self,
+        version_id: &str,
+    ) -> Result<Option<MojangVersionManifestVersion>> {
+        let version_file_path = self.launcher_paths.build_version_file_path(version_id);
+        let version_profile: Option<MojangVersionProfile> =
+            load_serialized_file(version_file_path).await?;
+
+        Ok(version_profile.map(|profile| profile.to_manifest_version()))
+    }
+
     pub async fn persist_config(&self) -> Result<()> {
         self.config.persist(self.launcher_paths.clone()).await
     }
 
-    pub async fn start_downloads(&mut self) -> Result<()> {
+    pub async fn persist_accounts(&self) -> Result<()> {
+        self.account_store.persist().await
+    }
+
+    pub async fn start_downloads(
+        &mut self,
+        progress: &dyn DownloadProgress,
+        cancellation: &CancellationToken,
+    ) -> Result<()> {
         let selected_manifest_version = self
             .selected_manifest_version
             .as_ref()
@@ -90,7 +170,12 @@ impl Launcher {
         let mut launcher_version = LauncherVersion::new(selected_manifest_version.clone());
 
         launcher_version
-            .start_downloads(&self.launcher_paths, &self.platform_data)
+            .start_downloads(
+                &self.launcher_paths,
+                &self.platform_data,
+                self.version_manifest_or_err()?,
+                self.download_context(progress, cancellation),
+            )
             .await?;
 
         self.selected_launcher_version = Some(launcher_version);
@@ -98,16 +183,144 @@ impl Launcher {
         Ok(())
     }
 
-    pub async fn launch_game(&self) -> Result<()> {
+    /// Installs the mod loader of the instance on first use, then selects
+    /// and downloads the version the instance launches.
+    pub async fn prepare_instance(
+        &mut self,
+        instance_id: &str,
+        progress: &dyn DownloadProgress,
+        cancellation: &CancellationToken,
+    ) -> Result<Instance> {
+        let mut instance = self
+            .instance_store
+            .find_instance(instance_id)
+            .cloned()
+            .ok_or(crate::Error::InstanceNotFound(instance_id.to_string()))?;
+
+        if instance.needs_mod_loader_install() {
+            let installed_version_id = self
+                .install_mod_loader(&instance, progress, cancellation)
+                .await?;
+            instance.installed_version_id = Some(installed_version_id);
+            self.instance_store.save_instance(instance.clone()).await?;
+        }
+
+        self.select_version(instance.launch_version_id().to_string())
+            .await?;
+        self.start_downloads(progress, cancellation).await?;
+
+        Ok(instance)
+    }
+
+    /// Moves the game directory of launchers before instances into a
+    /// "Default" instance, pinned to the last selected version or else the
+    /// latest release.
+    pub async fn migrate_legacy_game_dir(&mut self) -> Result<Option<Instance>> {
+        let version_id = match &self.config.last_selected_version_id {
+            Some(version_id) => version_id.clone(),
+            None => self.version_manifest_or_err()?.latest.release.clone(),
+        };
+
+        self.instance_store
+            .migrate_legacy_game_dir(version_id)
+            .await
+    }
+
+    async fn install_mod_loader(
+        &self,
+        instance: &Instance,
+        progress: &dyn DownloadProgress,
+        cancellation: &CancellationToken,
+    ) -> Result<String> {
+        let Some(mod_loader) = &instance.mod_loader else {
+            return Ok(instance.version_id.clone());
+        };
+
+        match mod_loader.kind {
+            ModLoaderKind::Fabric | ModLoaderKind::Quilt => {
+                let installer = match mod_loader.kind {
+                    ModLoaderKind::Fabric => FabricInstaller::fabric(),
+                    _ => FabricInstaller::quilt(),
+                };
+                installer
+                    .install(
+                        &self.launcher_paths,
+                        &instance.version_id,
+                        mod_loader.version.as_deref(),
+                    )
+                    .await
+            }
+            ModLoaderKind::Forge | ModLoaderKind::NeoForge => {
+                let installer = match mod_loader.kind {
+                    ModLoaderKind::Forge => ForgeInstaller::forge(),
+                    _ => ForgeInstaller::neoforge(),
+                };
+                let loader_version =
+                    mod_loader
+                        .version
+                        .as_deref()
+                        .ok_or(crate::Error::LoaderVersionNotFound(
+                            instance.version_id.clone(),
+                        ))?;
+                self.install_forge(&installer, loader_version, progress, cancellation)
+                    .await
+            }
+        }
+    }
+
+    /// Launches the instance with the version selected by `prepare_instance`.
+    /// `launch_options`, such as a quick play target, only apply to this
+    /// launch, on top of the ones saved in the instance.
+    pub async fn launch_game(
+        &mut self,
+        instance: &Instance,
+        launch_options: &LaunchOptions,
+        cancellation: &CancellationToken,
+    ) -> Result<GameExit> {
+        let launcher_version = self
+            .selected_launcher_version
+            .as_ref()
+            .filter(|version| version.manifest_version.id == instance.launch_version_id())
+            .ok_or(crate::Error::VersionNotSelectedError)?;
+        launcher_version
+            .copy_resources_to_instance(&self.launcher_paths, instance)
+            .await?;
+
+        let auth_session = self
+            .account_store
+            .selected_session(self.authenticator.as_ref())
+            .await?
+            .unwrap_or_else(|| {
+                AuthSession::offline(self.config.user_name.clone().unwrap_or_default())
+            });
+        self.config.launcher_client_id();
+
         launch_game(
             &self.launcher_paths,
             &self.platform_data,
-            &self.selected_launcher_version.as_ref().unwrap(),
+            launcher_version,
             &self.config,
+            &auth_session,
+            instance,
+            launch_options,
+            LaunchContext {
+                log_sink: self.log_sink.as_mut(),
+                cancellation,
+            },
         )
-        .await?;
+        .await
+    }
 
-        Ok(())
+    fn download_context<'a>(
+        &self,
+        progress: &'a dyn DownloadProgress,
+        cancellation: &'a CancellationToken,
+    ) -> DownloadContext<'a> {
+        DownloadContext {
+            parallelism: self.config.download_parallelism(),
+            progress,
+            cancellation,
+        }
     }
 
     fn version_manifest_or_err(&self) -> Result<&MojangVersionManifest> {
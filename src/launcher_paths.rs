@@ -49,6 +49,35 @@ impl LauncherPaths {
         )
     }
 
+    pub fn build_virtual_assets_path(&self, asset_index_id: &str) -> String {
+        let virtual_assets_dir = self.get_path(LauncherPath::AssetsVirtual);
+
+        join_paths(virtual_assets_dir, vec![asset_index_id])
+    }
+
+    pub fn build_instance_dir_path(&self, instance_id: &str) -> String {
+        let instances_dir = self.get_path(LauncherPath::InstancesDir);
+
+        join_paths(instances_dir, vec![instance_id])
+    }
+
+    pub fn build_instance_file_path(&self, instance_id: &str) -> String {
+        join_paths(
+            self.build_instance_dir_path(instance_id),
+            vec!["instance.json"],
+        )
+    }
+
+    pub fn build_instance_game_dir_path(&self, instance_id: &str) -> String {
+        join_paths(self.build_instance_dir_path(instance_id), vec!["minecraft"])
+    }
+
+    pub fn build_log_config_path(&self, log_config_id: &str) -> String {
+        let log_configs_dir = self.get_path(LauncherPath::AssetsLogConfigs);
+
+        join_paths(log_configs_dir, vec![log_config_id])
+    }
+
     pub fn build_natives_dir_path(&self, version_id: &str) -> String {
         let versions_dir = self.get_path(LauncherPath::VersionsDir);
 
@@ -77,7 +106,7 @@ impl LauncherPaths {
             LauncherPath::AssetsDir,
             LauncherPath::AssetsIndex,
             LauncherPath::AssetsObjects,
-            LauncherPath::GameDir,
+            LauncherPath::InstancesDir,
         ];
 
         for path in paths {
@@ -91,32 +120,46 @@ impl LauncherPaths {
 
 pub enum LauncherPath {
     LauncherConfig,
+    AccountsFile,
+    AccountSecretsFile,
     VersionsManifest,
     VersionsDir,
     LibrariesDir,
     AssetsDir,
     AssetsIndex,
     AssetsObjects,
-    GameDir,
+    AssetsVirtual,
+    AssetsLogConfigs,
+    InstancesDir,
+    /// Game directory every version shared before instances existed, only
+    /// read to migrate it.
+    LegacyGameDir,
     RuntimesDir,
     RuntimesIndex,
     RuntimesManifest,
+    TempDir,
 }
 
 impl LauncherPath {
     pub fn get_path(&self, base_path: String) -> String {
         let suffix = match self {
             LauncherPath::LauncherConfig => vec!["launcher_config.json"],
+            LauncherPath::AccountsFile => vec!["accounts.json"],
+            LauncherPath::AccountSecretsFile => vec!["account_secrets.json"],
             LauncherPath::VersionsManifest => vec!["versions", "version_manifest.json"],
             LauncherPath::VersionsDir => vec!["versions"],
             LauncherPath::LibrariesDir => vec!["libraries"],
             LauncherPath::AssetsDir => vec!["assets"],
             LauncherPath::AssetsIndex => vec!["assets", "indexes"],
             LauncherPath::AssetsObjects => vec!["assets", "objects"],
-            LauncherPath::GameDir => vec!["game"],
+            LauncherPath::AssetsVirtual => vec!["assets", "virtual"],
+            LauncherPath::AssetsLogConfigs => vec!["assets", "log_configs"],
+            LauncherPath::InstancesDir => vec!["instances"],
+            LauncherPath::LegacyGameDir => vec!["game"],
             LauncherPath::RuntimesDir => vec!["runtimes"],
             LauncherPath::RuntimesIndex => vec!["runtimes", "index.json"],
             LauncherPath::RuntimesManifest => vec!["runtimes", "manifests"],
+            LauncherPath::TempDir => vec!["temp"],
         };
 
         join_paths(base_path, suffix)
@@ -1,106 +1,436 @@
-use tokio::io::AsyncBufReadExt;
-use tokio::process::Command;
+use std::{
+    collections::VecDeque,
+    process::ExitStatus,
+    time::{Duration, SystemTime},
+};
+
+use tokio::{
+    io::{AsyncBufRead, AsyncBufReadExt, BufReader},
+    process::{Child, Command},
+    sync::mpsc::{unbounded_channel, UnboundedSender},
+    time::Instant,
+};
+use tokio_util::sync::CancellationToken;
 
 use crate::{
+    auth::AuthSession,
     config::LauncherConfig,
+    game_exit::{analyze_game_exit, GameExit},
+    game_log::{GameLogEntry, GameLogParser, GameLogSink, GameOutputStream},
+    instances::Instance,
+    jvm_settings::JvmSettings,
+    launch_hooks::HookEnvironment,
+    launch_options::LaunchOptions,
+    launch_placeholders::LaunchPlaceholders,
     launcher_paths::join_paths,
     launcher_version::LauncherVersion,
+    logging_config::Log4jMitigation,
     model::mojang::MojangVersion,
     platform::{PlatformData, PlatformType},
-    LauncherPaths, Result,
+    LauncherPath, LauncherPaths, Result,
 };
 
+const LAUNCHER_NAME: &str = env!("CARGO_PKG_NAME");
+const LAUNCHER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Lines of game output kept to classify a crash.
+const RECENT_OUTPUT_LINES: usize = 200;
+
+/// How long a cancelled game gets to shut down before it is killed.
+const GRACEFUL_TERMINATION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long output is still read after the game exited, since processes a
+/// wrapper command left behind may keep its pipes open.
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Versions without an `arguments` object only list game arguments.
+const LEGACY_JVM_ARGUMENTS: [&str; 3] = [
+    "-Djava.library.path=${natives_directory}",
+    "-cp",
+    "${classpath}",
+];
+
+/// Where the output of a running game goes and how it is stopped.
+pub struct LaunchContext<'a> {
+    pub log_sink: &'a mut dyn GameLogSink,
+    /// Cancelling asks the game to quit and kills it if it is still running
+    /// after a timeout.
+    pub cancellation: &'a CancellationToken,
+}
+
+/// `launch_options` only apply to this launch, on top of the ones saved in
+/// the instance settings.
+#[allow(clippy::too_many_arguments)]
 pub async fn launch_game(
     launcher_paths: &LauncherPaths,
     platform_data: &PlatformData,
     launcher_version: &LauncherVersion,
     launcher_config: &LauncherConfig,
-) -> Result<()> {
-    let mut command_arguments = build_game_launch_command(
+    auth_session: &AuthSession,
+    instance: &Instance,
+    launch_options: &LaunchOptions,
+    context: LaunchContext<'_>,
+) -> Result<GameExit> {
+    if context.cancellation.is_cancelled() {
+        return Err(crate::Error::Cancelled);
+    }
+
+    let hooks = instance
+        .settings
+        .hooks
+        .merged_with(&launcher_config.launch_hooks);
+    let jvm_settings = resolve_jvm_settings(launcher_config, instance)?;
+    let launch_options = launch_options.merged_with(&instance.settings.launch_options);
+    let command_arguments = hooks.wrap_command(build_game_launch_command(
         launcher_paths,
         platform_data,
         launcher_version,
         launcher_config,
-    )?;
+        auth_session,
+        instance,
+        &launch_options,
+        &jvm_settings,
+    )?);
 
-    println!(
-        "Launching game with command: {:?}",
-        command_arguments.clone().join(" ")
-    );
+    let game_directory = instance.game_directory_path(launcher_paths);
 
-    let mut command_builder = {
-        let mut builder = Command::new(command_arguments.remove(0));
-        builder
-            .args(&command_arguments)
-            .stdin(std::process::Stdio::inherit())
-            .stdout(std::process::Stdio::inherit())
-            .stderr(std::process::Stdio::inherit())
-            .current_dir(launcher_paths.get_path(crate::LauncherPath::GameDir))
-            .stderr(std::process::Stdio::piped());
-        builder
+    let version_id = launcher_version
+        .mojang_version
+        .as_ref()
+        .map(|version| version.id.as_str())
+        .unwrap_or(instance.launch_version_id());
+    let mut hook_environment = HookEnvironment {
+        instance,
+        version_id,
+        game_directory: &game_directory,
+        exit_code: None,
     };
+    hooks
+        .run_pre_launch(&hook_environment, context.cancellation)
+        .await?;
 
-    let mut child = command_builder.spawn()?;
+    let started_at = SystemTime::now();
+    let child = spawn_game(command_arguments, &jvm_settings, &game_directory)?;
 
     println!("Game launched with PID: {:?}", child.id());
 
-    child.wait().await?;
+    let GameProcessExit {
+        status,
+        cancelled,
+        mut recent_output,
+    } = supervise_game(child, context).await?;
+
+    hook_environment.exit_code = status.code();
+    let post_exit_result = hooks.run_post_exit(&hook_environment).await;
+
+    let mut game_exit = analyze_game_exit(
+        &game_directory,
+        started_at,
+        status,
+        cancelled,
+        recent_output.make_contiguous(),
+    )
+    .await?;
+    // The game has already exited, so a failing hook is only reported.
+    game_exit.post_exit_hook_error = post_exit_result.err().map(|error| format!("{:?}", error));
+
+    Ok(game_exit)
+}
+
+/// How the game process ended, with the output kept to classify a crash.
+struct GameProcessExit {
+    status: ExitStatus,
+    cancelled: bool,
+    recent_output: VecDeque<String>,
+}
+
+/// On unix the game gets its own process group, so that signals also reach
+/// the game when it runs under a wrapper command such as `sh -c`.
+fn spawn_game(
+    mut command_arguments: Vec<String>,
+    jvm_settings: &JvmSettings,
+    game_directory: &str,
+) -> Result<Child> {
+    let mut command_builder = Command::new(command_arguments.remove(0));
+    command_builder
+        .args(&command_arguments)
+        .envs(&jvm_settings.environment)
+        .stdin(std::process::Stdio::inherit())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .current_dir(game_directory);
+
+    #[cfg(unix)]
+    command_builder.process_group(0);
+
+    Ok(command_builder.spawn()?)
+}
+
+/// Forwards the game output to the log sink until the game exits, asking it
+/// to quit on cancellation and killing it if it does not.
+async fn supervise_game(mut child: Child, context: LaunchContext<'_>) -> Result<GameProcessExit> {
+    // Kept for signalling the process group after the game itself exited.
+    let pid = child.id();
+
+    let (sender, mut receiver) = unbounded_channel();
+    if let Some(stdout) = child.stdout.take() {
+        tokio::spawn(forward_output(
+            BufReader::new(stdout),
+            GameOutputStream::Stdout,
+            sender.clone(),
+        ));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        tokio::spawn(forward_output(
+            BufReader::new(stderr),
+            GameOutputStream::Stderr,
+            sender,
+        ));
+    }
+
+    // The channel closes once both streams reach EOF. Output still open
+    // after the game exited is only read until a short deadline.
+    let mut recent_output = VecDeque::with_capacity(RECENT_OUTPUT_LINES);
+    let mut cancelled = false;
+    let mut exit_status = None;
+    let mut kill_deadline = None;
+    let mut output_deadline = None;
+    loop {
+        tokio::select! {
+            entry = receiver.recv() => {
+                let Some(entry) = entry else {
+                    break;
+                };
+
+                if recent_output.len() == RECENT_OUTPUT_LINES {
+                    recent_output.pop_front();
+                }
+                recent_output.push_back(entry.format());
+
+                if let Err(err) = context.log_sink.handle(entry) {
+                    eprintln!("Failed to write game log: {:?}", err);
+                }
+            }
+            status = child.wait(), if exit_status.is_none() => {
+                exit_status = Some(status?);
+                output_deadline = Some(Instant::now() + OUTPUT_DRAIN_TIMEOUT);
+            }
+            _ = context.cancellation.cancelled(), if !cancelled => {
+                cancelled = true;
+                request_termination(pid).await?;
+                kill_deadline = Some(Instant::now() + GRACEFUL_TERMINATION_TIMEOUT);
+            }
+            _ = sleep_until(kill_deadline) => {
+                kill_deadline = None;
+                force_termination(pid, &mut child).await?;
+            }
+            _ = sleep_until(output_deadline) => break,
+        }
+    }
+
+    let status = match exit_status {
+        Some(status) => status,
+        None => child.wait().await?,
+    };
+
+    Ok(GameProcessExit {
+        status,
+        cancelled,
+        recent_output,
+    })
+}
+
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Asks the game and the processes it started to quit on their own, so the
+/// game can save the world before exiting.
+async fn request_termination(pid: Option<u32>) -> Result<()> {
+    let Some(pid) = pid else {
+        return Ok(());
+    };
+
+    #[cfg(unix)]
+    // SAFETY: `kill` only sends a signal to the process group spawned above.
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), libc::SIGTERM);
+    }
+
+    // Without `/F`, taskkill asks the windows of the process tree to close.
+    #[cfg(windows)]
+    Command::new("taskkill")
+        .args(["/PID", &pid.to_string(), "/T"])
+        .status()
+        .await?;
+
+    #[cfg(not(any(unix, windows)))]
+    let _ = pid;
+
+    Ok(())
+}
+
+/// Kills the game and the processes it started.
+pub(crate) async fn force_termination(pid: Option<u32>, child: &mut Child) -> Result<()> {
+    #[cfg(unix)]
+    if let Some(pid) = pid {
+        // SAFETY: `kill` only sends a signal to the process group spawned
+        // above.
+        unsafe {
+            libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+        }
+    }
+
+    #[cfg(windows)]
+    if let Some(pid) = pid {
+        Command::new("taskkill")
+            .args(["/PID", &pid.to_string(), "/T", "/F"])
+            .status()
+            .await?;
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    let _ = pid;
+
+    // Covers the game itself when the group could not be signalled; fails
+    // once the game was already reaped.
+    let _ = child.start_kill();
 
     Ok(())
 }
+
+/// Reads one output stream of the game, parsing log4j events out of stdout.
+async fn forward_output(
+    mut reader: impl AsyncBufRead + Unpin,
+    stream: GameOutputStream,
+    sender: UnboundedSender<GameLogEntry>,
+) -> Result<()> {
+    let mut parser = GameLogParser::default();
+    let mut buffer = Vec::new();
+
+    while reader.read_until(b'\n', &mut buffer).await? > 0 {
+        let line = String::from_utf8_lossy(&buffer)
+            .trim_end_matches(['\r', '\n'])
+            .to_string();
+        buffer.clear();
+
+        let entry = match stream {
+            GameOutputStream::Stdout => parser.push_line(line),
+            GameOutputStream::Stderr => Some(GameLogEntry::Line { stream, line }),
+        };
+
+        if let Some(entry) = entry {
+            if sender.send(entry).is_err() {
+                return Ok(());
+            }
+        }
+    }
+
+    if let Some(entry) = parser.finish() {
+        let _ = sender.send(entry);
+    }
+
+    Ok(())
+}
+
+/// Builds the command in this order:
+///
+/// 1. the Java binary, from the JVM settings or the managed runtime;
+/// 2. the JVM arguments of the version, then its logging arguments;
+/// 3. `-Xms`/`-Xmx` from the JVM settings;
+/// 4. the global extra JVM arguments, then those of the instance;
+/// 5. the main class and the game arguments of the version.
+///
+/// When the JVM sees the same option twice the last one wins, so settings
+/// override the version and the instance overrides the global settings.
+/// `launch_options` and `jvm_settings` are the ones already merged with the
+/// instance settings.
+#[allow(clippy::too_many_arguments)]
 pub fn build_game_launch_command(
     launcher_paths: &LauncherPaths,
     platform_data: &PlatformData,
     launcher_version: &LauncherVersion,
     launcher_config: &LauncherConfig,
+    auth_session: &AuthSession,
+    instance: &Instance,
+    launch_options: &LaunchOptions,
+    jvm_settings: &JvmSettings,
 ) -> Result<Vec<String>> {
     let mut command = Vec::new();
+    let rule_context = launch_options.rule_context(platform_data);
 
     let mojang_version = launcher_version
         .mojang_version
         .as_ref()
         .ok_or(crate::Error::VersionNotSelectedError)?;
 
-    let runtime_name = mojang_version.java_version.component.clone();
-    let runtime_path = launcher_paths.build_runtime_path(&runtime_name);
-
-    command.push(get_java_executable_path(&platform_data, runtime_path));
+    let java_path = match &jvm_settings.java_path {
+        Some(java_path) => java_path.clone(),
+        None => get_java_executable_path(
+            platform_data,
+            launcher_paths.build_runtime_path(&mojang_version.java_version.component),
+        ),
+    };
+    command.push(java_path);
 
-    let jvm_arguments = mojang_version
-        .arguments
-        .clone()
-        .map(|arguments| arguments.select_arguments(&platform_data));
-    if let Some((_, jvm_arguments)) = jvm_arguments {
-        command.extend(jvm_arguments);
-    }
+    let (game_arguments, jvm_arguments) = match &mojang_version.arguments {
+        Some(arguments) => arguments.select_arguments(&rule_context),
+        None => (
+            mojang_version
+                .minecraft_arguments
+                .as_ref()
+                .map(|arguments| arguments.split(' ').map(|s| s.to_string()).collect())
+                .unwrap_or_default(),
+            LEGACY_JVM_ARGUMENTS
+                .iter()
+                .map(|argument| argument.to_string())
+                .collect(),
+        ),
+    };
 
-    command.push(
-        "-Djava.library.path=".to_string()
-            + launcher_paths
-                .build_natives_dir_path(&launcher_version.manifest_version.id)
-                .as_str(),
+    let mut placeholders = build_launch_placeholders(
+        launcher_paths,
+        platform_data,
+        launcher_version,
+        mojang_version,
+        launcher_config,
+        auth_session,
+        instance,
     );
+    launch_options.insert_placeholders(
+        &mut placeholders,
+        &instance.game_directory_path(launcher_paths),
+    );
+    let strict = launch_options.strict_placeholders;
 
-    command.push("-cp".to_string());
-    command.push(build_java_class_path(
-        &launcher_paths,
-        &platform_data,
-        &launcher_version,
-    ));
-
+    command.extend(placeholders.substitute_all(&jvm_arguments, strict)?);
+    command.extend(build_logging_arguments(launcher_version, mojang_version));
+    command.extend(jvm_settings.memory_arguments());
+    command.extend(jvm_settings.extra_arguments.iter().cloned());
     command.push(mojang_version.main_class.clone());
-
-    command.extend(build_minecraft_arguments(
-        &launcher_paths,
-        &platform_data,
-        &mojang_version,
-        &launcher_config,
-    ));
+    command.extend(placeholders.substitute_all(&game_arguments, strict)?);
 
     Ok(command)
 }
 
-fn get_java_executable_path(platform_data: &PlatformData, runtime_base_path: String) -> String {
+/// The instance settings over the global ones, validated.
+pub fn resolve_jvm_settings(
+    launcher_config: &LauncherConfig,
+    instance: &Instance,
+) -> Result<JvmSettings> {
+    let jvm_settings = instance
+        .settings
+        .jvm
+        .merged_with(&launcher_config.jvm_settings);
+    jvm_settings.validate()?;
+
+    Ok(jvm_settings)
+}
+
+pub fn get_java_executable_path(platform_data: &PlatformData, runtime_base_path: String) -> String {
     let java_executable_name = match platform_data.platform_type {
         PlatformType::Windows => "java.exe",
         _ => "java",
@@ -113,11 +443,11 @@ fn build_java_class_path(
     launcher_paths: &LauncherPaths,
     platform_data: &PlatformData,
     launcher_version: &LauncherVersion,
+    mojang_version: &MojangVersion,
 ) -> String {
     let mut paths: Vec<String> = Vec::new();
 
-    let client_file_path =
-        launcher_paths.build_client_file_path(&launcher_version.manifest_version.id);
+    let client_file_path = launcher_paths.build_client_file_path(mojang_version.client_jar_id());
     paths.push(client_file_path);
 
     let libraries = launcher_version.libraries.as_ref().unwrap();
@@ -127,61 +457,177 @@ fn build_java_class_path(
         .map(|library| launcher_paths.build_library_path(&library.get_path()))
         .for_each(|path| paths.push(path));
 
-    let separator = match platform_data.platform_type {
-        PlatformType::Windows => ";",
-        _ => ":",
-    };
+    paths.join(platform_data.platform_type.classpath_separator())
+}
+
+fn build_logging_arguments(
+    launcher_version: &LauncherVersion,
+    mojang_version: &MojangVersion,
+) -> Vec<String> {
+    let mut arguments = Vec::new();
+
+    let logging = mojang_version.client_logging();
+    if let (Some(logging), Some(config_path)) = (logging, &launcher_version.logging_config_path) {
+        arguments.push(logging.argument.replace("${path}", config_path));
+    }
 
-    paths.join(separator)
+    if let Some(argument) = Log4jMitigation::for_version(mojang_version).jvm_argument() {
+        arguments.push(argument.to_string());
+    }
+
+    arguments
 }
 
-fn build_minecraft_arguments(
+fn build_launch_placeholders(
     launcher_paths: &LauncherPaths,
     platform_data: &PlatformData,
+    launcher_version: &LauncherVersion,
     mojang_version: &MojangVersion,
     launcher_config: &LauncherConfig,
-) -> Vec<String> {
-    let arguments = mojang_version
-        .arguments
-        .as_ref()
-        .map(|arguments| arguments.select_arguments(&platform_data).0)
-        .unwrap_or_else(|| {
-            mojang_version
-                .minecraft_arguments
-                .as_ref()
-                .map(|arguments| arguments.split(" ").map(|s| s.to_string()).collect())
-                .unwrap_or_default()
-        });
+    auth_session: &AuthSession,
+    instance: &Instance,
+) -> LaunchPlaceholders {
+    let game_directory = instance.game_directory_path(launcher_paths);
+    let assets_root = launcher_paths.get_path(LauncherPath::AssetsDir);
 
-    arguments
-        .iter()
-        .map(|argument| {
-            argument
-                .replace(
-                    "${auth_player_name}",
-                    launcher_config
-                        .user_name
-                        .as_ref()
-                        .unwrap_or(&"".to_string()),
-                )
-                .replace("${version_name}", &mojang_version.id)
-                .replace(
-                    "${game_directory}",
-                    launcher_paths
-                        .get_path(crate::LauncherPath::GameDir)
-                        .as_str(),
-                )
-                .replace(
-                    "${assets_root}",
-                    launcher_paths
-                        .get_path(crate::LauncherPath::AssetsDir)
-                        .as_str(),
-                )
-                .replace("${assets_index_name}", &mojang_version.asset_index.id)
-                .replace("${auth_uuid}", "abcde")
-                .replace("${auth_access_token}", "token")
-                .replace("${user_type}", "msa")
-                .replace("${user_properties}", "{}")
-        })
-        .collect()
+    let mut placeholders = LaunchPlaceholders::default();
+
+    placeholders.insert("auth_player_name", auth_session.username.clone());
+    placeholders.insert("auth_uuid", auth_session.uuid.clone());
+    placeholders.insert("auth_access_token", auth_session.access_token.clone());
+    placeholders.insert(
+        "auth_session",
+        format!("token:{}:{}", auth_session.access_token, auth_session.uuid),
+    );
+    placeholders.insert("auth_xuid", auth_session.xuid.clone().unwrap_or_default());
+    placeholders.insert("user_type", auth_session.user_type.clone());
+    placeholders.insert("user_properties", "{}");
+    placeholders.insert(
+        "clientid",
+        launcher_config
+            .launcher_client_id
+            .clone()
+            .unwrap_or_default(),
+    );
+
+    placeholders.insert("version_name", mojang_version.id.clone());
+    placeholders.insert(
+        "version_type",
+        launcher_version.manifest_version.r#type.id(),
+    );
+    placeholders.insert("game_directory", game_directory.clone());
+    placeholders.insert("assets_root", assets_root.clone());
+    placeholders.insert(
+        "game_assets",
+        build_game_assets_path(
+            launcher_paths,
+            launcher_version,
+            mojang_version,
+            instance,
+            assets_root,
+        ),
+    );
+    placeholders.insert("assets_index_name", mojang_version.asset_index.id.clone());
+
+    placeholders.insert(
+        "natives_directory",
+        launcher_paths.build_natives_dir_path(&launcher_version.manifest_version.id),
+    );
+    placeholders.insert(
+        "library_directory",
+        launcher_paths.get_path(LauncherPath::LibrariesDir),
+    );
+    placeholders.insert(
+        "classpath",
+        build_java_class_path(
+            launcher_paths,
+            platform_data,
+            launcher_version,
+            mojang_version,
+        ),
+    );
+    placeholders.insert(
+        "classpath_separator",
+        platform_data.platform_type.classpath_separator(),
+    );
+    placeholders.insert("launcher_name", LAUNCHER_NAME);
+    placeholders.insert("launcher_version", LAUNCHER_VERSION);
+
+    placeholders
+}
+
+/// Legacy versions read assets by name from the tree rebuilt for their
+/// asset index instead of the shared objects store.
+fn build_game_assets_path(
+    launcher_paths: &LauncherPaths,
+    launcher_version: &LauncherVersion,
+    mojang_version: &MojangVersion,
+    instance: &Instance,
+    assets_root: String,
+) -> String {
+    match &launcher_version.asset_index {
+        Some(asset_index) if asset_index.r#virtual => {
+            launcher_paths.build_virtual_assets_path(&mojang_version.asset_index.id)
+        }
+        Some(asset_index) if asset_index.map_to_resources => {
+            instance.resources_dir_path(launcher_paths)
+        }
+        _ => assets_root,
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::game_log::ConsoleLogSink;
+
+    fn spawn_shell(script: &str) -> Child {
+        let command = vec!["sh".to_string(), "-c".to_string(), script.to_string()];
+        spawn_game(command, &JvmSettings::default(), ".").unwrap()
+    }
+
+    #[tokio::test]
+    async fn cancellation_reaches_processes_started_by_a_wrapper() {
+        // The trailing command keeps the shell from exec-ing `sleep`.
+        let child = spawn_shell("sleep 30; true");
+        let cancellation = CancellationToken::new();
+        cancellation.cancel();
+
+        let context = LaunchContext {
+            log_sink: &mut ConsoleLogSink,
+            cancellation: &cancellation,
+        };
+        let started_at = Instant::now();
+        let exit = tokio::time::timeout(Duration::from_secs(5), supervise_game(child, context))
+            .await
+            .expect("the game kept running after cancellation")
+            .unwrap();
+
+        // Only the whole group exiting closes the output before the drain
+        // deadline.
+        assert!(started_at.elapsed() < OUTPUT_DRAIN_TIMEOUT);
+        assert!(exit.cancelled);
+        assert!(!exit.status.success());
+    }
+
+    #[tokio::test]
+    async fn returns_when_the_wrapper_exits_before_its_children() {
+        let child = spawn_shell("sleep 10 & echo started");
+        let cancellation = CancellationToken::new();
+
+        let context = LaunchContext {
+            log_sink: &mut ConsoleLogSink,
+            cancellation: &cancellation,
+        };
+        let exit = tokio::time::timeout(Duration::from_secs(5), supervise_game(child, context))
+            .await
+            .expect("waited for output held open by a child of the wrapper")
+            .unwrap();
+
+        assert!(exit.status.success());
+        assert!(exit
+            .recent_output
+            .iter()
+            .any(|line| line.contains("started")));
+    }
 }
@@ -1,13 +1,20 @@
 use std::{io::Cursor, path::PathBuf};
 
-use futures::StreamExt;
+use futures::future::BoxFuture;
 
 use crate::{
-    files::{load_file_or_download, load_file_or_download_serialized},
+    download_plan::{DownloadContext, DownloadKind, DownloadPlan, DownloadTask},
+    download_progress::{DownloadEvent, DownloadStage},
+    files::{
+        create_parent_folders, load_file_or_download_serialized, load_serialized_file, save_file,
+    },
+    instances::Instance,
     launcher_paths::LauncherPaths,
+    logging_config::Log4jMitigation,
     model::mojang::{
-        MojangAssetIndexFile, MojangVersion, MojangVersionArtifactKey,
-        MojangVersionManifestVersion, RuntimeIndex, RuntimeManifest, VersionLibrary,
+        MojangAssetIndexFile, MojangVersion, MojangVersionArtifactKey, MojangVersionManifest,
+        MojangVersionManifestVersion, MojangVersionProfile, RuntimeIndex, RuntimeManifest,
+        VersionLibrary,
     },
     platform::PlatformData,
     LauncherPath, Result,
@@ -22,6 +29,7 @@ pub struct LauncherVersion {
     pub mojang_version: Option<MojangVersion>,
     pub libraries: Option<Vec<VersionLibrary>>,
     pub asset_index: Option<MojangAssetIndexFile>,
+    pub logging_config_path: Option<String>,
 }
 
 impl LauncherVersion {
@@ -31,6 +39,7 @@ impl LauncherVersion {
             mojang_version: None,
             libraries: None,
             asset_index: None,
+            logging_config_path: None,
         }
     }
 
@@ -38,91 +47,232 @@ impl LauncherVersion {
         &mut self,
         launcher_paths: &LauncherPaths,
         platform_data: &PlatformData,
+        version_manifest: &MojangVersionManifest,
+        context: DownloadContext<'_>,
     ) -> Result<()> {
-        let mojang_version = self.download_mojang_version(launcher_paths).await?;
-        self.download_client_file(launcher_paths, &mojang_version, &self.manifest_version.id)
-            .await?;
-
-        self.download_libraries(&mojang_version, launcher_paths, platform_data)
+        context
+            .progress
+            .on_event(DownloadEvent::StageStarted(DownloadStage::Metadata));
+        let mojang_version = self
+            .download_mojang_version(launcher_paths, version_manifest)
             .await?;
-
+        let libraries = mojang_version.filter_libraries_by_platform_data(platform_data)?;
+        self.libraries = Some(libraries.clone());
         let asset_index = self
             .download_asset_index(&mojang_version, launcher_paths)
             .await?;
-        self.download_asset_objects(&asset_index, launcher_paths)
+        let runtime_manifest = self
+            .download_runtime_manifest(launcher_paths, &mojang_version, platform_data)
             .await?;
-        self.download_runtime(&launcher_paths, &mojang_version, &platform_data)
+
+        let mut plan = DownloadPlan::default();
+        Self::plan_client_file(&mut plan, launcher_paths, &mojang_version)?;
+        Self::plan_libraries(&mut plan, launcher_paths, &libraries);
+        Self::plan_asset_objects(&mut plan, launcher_paths, &asset_index);
+        let runtime_path =
+            launcher_paths.build_runtime_path(&mojang_version.java_version.component);
+        runtime_manifest.plan_downloads(&mut plan, runtime_path.clone());
+        Self::plan_logging_config(&mut plan, launcher_paths, &mojang_version);
+
+        context.check_cancelled()?;
+        context
+            .progress
+            .on_event(DownloadEvent::StageStarted(DownloadStage::Files));
+        plan.execute(context).await?;
+        runtime_manifest.mark_executables(runtime_path).await?;
+
+        context.check_cancelled()?;
+        context
+            .progress
+            .on_event(DownloadEvent::StageStarted(DownloadStage::LegacyAssets));
+        self.reconstruct_legacy_assets(
+            &mojang_version.asset_index.id,
+            &asset_index,
+            launcher_paths,
+        )
+        .await?;
+
+        context.check_cancelled()?;
+        context
+            .progress
+            .on_event(DownloadEvent::StageStarted(DownloadStage::LoggingConfig));
+        self.prepare_logging_config(launcher_paths, &mojang_version)
             .await?;
 
+        context.check_cancelled()?;
+        context
+            .progress
+            .on_event(DownloadEvent::StageStarted(DownloadStage::Natives));
         self.extract_natives(launcher_paths).await?;
 
+        context.progress.on_event(DownloadEvent::Finished);
+
         Ok(())
     }
 
+    /// Fetches only what is needed to run Java against this version, such as
+    /// the client jar and the managed runtime, without libraries or assets.
+    pub async fn download_client_and_runtime(
+        &mut self,
+        launcher_paths: &LauncherPaths,
+        platform_data: &PlatformData,
+        version_manifest: &MojangVersionManifest,
+        context: DownloadContext<'_>,
+    ) -> Result<MojangVersion> {
+        let mojang_version = self
+            .download_mojang_version(launcher_paths, version_manifest)
+            .await?;
+        let runtime_manifest = self
+            .download_runtime_manifest(launcher_paths, &mojang_version, platform_data)
+            .await?;
+
+        let mut plan = DownloadPlan::default();
+        Self::plan_client_file(&mut plan, launcher_paths, &mojang_version)?;
+        let runtime_path =
+            launcher_paths.build_runtime_path(&mojang_version.java_version.component);
+        runtime_manifest.plan_downloads(&mut plan, runtime_path.clone());
+        plan.execute(context).await?;
+        runtime_manifest.mark_executables(runtime_path).await?;
+
+        Ok(mojang_version)
+    }
+
     async fn download_mojang_version(
         &mut self,
         launcher_paths: &LauncherPaths,
+        version_manifest: &MojangVersionManifest,
     ) -> Result<MojangVersion> {
         let version_id = self.manifest_version.id.clone();
         let version_file_path = launcher_paths.build_version_file_path(&version_id);
 
         let download_url = self.manifest_version.url.clone();
-        let mojang_version: MojangVersion =
-            load_file_or_download_serialized(version_file_path, download_url).await?;
+        let version_content: serde_json::Value = load_file_or_download_serialized(
+            version_file_path,
+            download_url,
+            self.manifest_version.sha1.as_deref(),
+            None,
+        )
+        .await?;
+
+        let mojang_version = Self::resolve_version_content(
+            launcher_paths,
+            version_manifest,
+            version_content,
+            vec![version_id],
+        )
+        .await?;
 
         self.mojang_version = Some(mojang_version.clone());
 
         Ok(mojang_version)
     }
 
-    async fn download_client_file(
-        &self,
-        launcher_paths: &LauncherPaths,
-        mojang_version: &MojangVersion,
-        version_id: &str,
-    ) -> Result<()> {
-        let client_file_path = launcher_paths.build_client_file_path(version_id);
-
-        let download_url = mojang_version
-            .downloads
-            .get(&MojangVersionArtifactKey::Client)
-            .ok_or(crate::Error::ClientDownloadNotFound(version_id.to_string()))?
-            .url
-            .clone();
-        let _ = load_file_or_download(client_file_path, download_url).await?;
+    /// Merges a version JSON onto every version it inherits from, such as
+    /// OptiFine on Forge on vanilla. `chain` holds the ids already visited.
+    fn resolve_version_content<'a>(
+        launcher_paths: &'a LauncherPaths,
+        version_manifest: &'a MojangVersionManifest,
+        version_content: serde_json::Value,
+        mut chain: Vec<String>,
+    ) -> BoxFuture<'a, Result<MojangVersion>> {
+        Box::pin(async move {
+            let version_profile: MojangVersionProfile =
+                serde_json::from_value(version_content.clone())?;
+            let Some(parent_id) = version_profile.inherits_from.clone() else {
+                return Ok(serde_json::from_value(version_content)?);
+            };
+            if chain.contains(&parent_id) {
+                return Err(crate::Error::VersionInheritanceCycle(parent_id));
+            }
+
+            let parent_content =
+                Self::download_parent_version(launcher_paths, version_manifest, &parent_id).await?;
+            chain.push(parent_id);
+            let parent_version = Self::resolve_version_content(
+                launcher_paths,
+                version_manifest,
+                parent_content,
+                chain,
+            )
+            .await?;
 
-        Ok(())
+            Ok(version_profile.resolve(parent_version))
+        })
     }
 
-    async fn download_libraries(
-        &mut self,
-        mojang_version: &MojangVersion,
+    async fn download_parent_version(
         launcher_paths: &LauncherPaths,
-        platform_data: &PlatformData,
-    ) -> Result<()> {
-        let filtered_libraries = mojang_version.filter_libraries_by_platform_data(platform_data)?;
-        self.libraries = Some(filtered_libraries.clone());
-
-        for library in filtered_libraries {
-            self.download_library(library, launcher_paths).await?;
+        version_manifest: &MojangVersionManifest,
+        parent_id: &str,
+    ) -> Result<serde_json::Value> {
+        let parent_file_path = launcher_paths.build_version_file_path(parent_id);
+
+        // Parents that are not in the manifest, like an installed Forge
+        // version, can only be used as they are on disk.
+        match version_manifest.find_version_by_id(parent_id.to_string()) {
+            Some(parent_version) => {
+                load_file_or_download_serialized(
+                    parent_file_path,
+                    parent_version.url,
+                    parent_version.sha1.as_deref(),
+                    None,
+                )
+                .await
+            }
+            None => load_serialized_file(parent_file_path)
+                .await?
+                .ok_or(crate::Error::VersionNotFound(parent_id.to_string())),
         }
-
-        Ok(())
     }
 
-    async fn download_library(
-        &self,
-        library: VersionLibrary,
+    fn plan_client_file(
+        plan: &mut DownloadPlan,
         launcher_paths: &LauncherPaths,
+        mojang_version: &MojangVersion,
     ) -> Result<()> {
-        let library_path = launcher_paths.build_library_path(&library.get_path());
-
-        let download_url = library.url.clone();
-        let _ = load_file_or_download(library_path, download_url).await?;
+        let version_id = mojang_version.client_jar_id();
+        let client_artifact = mojang_version
+            .downloads
+            .get(&MojangVersionArtifactKey::Client)
+            .ok_or(crate::Error::ClientDownloadNotFound(version_id.to_string()))?;
+
+        plan.add(DownloadTask {
+            kind: DownloadKind::Client,
+            path: launcher_paths.build_client_file_path(version_id),
+            url: client_artifact.url.clone(),
+            sha1: Some(client_artifact.sha1.clone()),
+            size: Some(client_artifact.size),
+        });
 
         Ok(())
     }
 
+    fn plan_libraries(
+        plan: &mut DownloadPlan,
+        launcher_paths: &LauncherPaths,
+        libraries: &[VersionLibrary],
+    ) {
+        for library in libraries {
+            // Libraries without an url, like the ones a Forge installer
+            // produces, are already on disk.
+            if library.url.is_empty() {
+                continue;
+            }
+
+            plan.add(DownloadTask {
+                kind: if library.is_native() {
+                    DownloadKind::Native
+                } else {
+                    DownloadKind::Library
+                },
+                path: launcher_paths.build_library_path(&library.get_path()),
+                url: library.url.clone(),
+                sha1: library.sha1.clone(),
+                size: library.size,
+            });
+        }
+    }
+
     async fn download_asset_index(
         &mut self,
         mojang_version: &MojangVersion,
@@ -132,33 +282,121 @@ impl LauncherVersion {
         let asset_index_path = launcher_paths.build_asset_index_path(&asset_index.id);
 
         let download_url = asset_index.url.clone();
-        let result: MojangAssetIndexFile =
-            load_file_or_download_serialized(asset_index_path, download_url).await?;
+        let result: MojangAssetIndexFile = load_file_or_download_serialized(
+            asset_index_path,
+            download_url,
+            Some(&asset_index.sha1),
+            Some(asset_index.size),
+        )
+        .await?;
 
         self.asset_index = Some(result.clone());
 
         Ok(result)
     }
 
-    async fn download_asset_objects(
+    fn plan_asset_objects(
+        plan: &mut DownloadPlan,
+        launcher_paths: &LauncherPaths,
+        asset_index: &MojangAssetIndexFile,
+    ) {
+        let assets_objects_base_folder = launcher_paths.get_path(LauncherPath::AssetsObjects);
+
+        for object in asset_index.objects.values() {
+            plan.add(DownloadTask {
+                kind: DownloadKind::AssetObject,
+                path: object.build_file_path(&assets_objects_base_folder),
+                url: object.build_download_url(ASSET_INDEX_OBJECT_DOWNLOAD_BASE_URL),
+                sha1: Some(object.hash.clone()),
+                size: Some(object.size),
+            });
+        }
+    }
+
+    /// Old versions read assets by name instead of by hash, so the objects are
+    /// copied to the layout their asset index asks for.
+    async fn reconstruct_legacy_assets(
         &self,
+        asset_index_id: &str,
         asset_index: &MojangAssetIndexFile,
         launcher_paths: &LauncherPaths,
     ) -> Result<()> {
-        let futures = asset_index.objects.values().map(|object| async move {
-            let assets_objects_base_folder =
-                launcher_paths.get_path(crate::LauncherPath::AssetsObjects);
-            let file_path = object.build_file_path(&assets_objects_base_folder);
-            let download_url = object.build_download_url(ASSET_INDEX_OBJECT_DOWNLOAD_BASE_URL);
+        if !asset_index.r#virtual {
+            return Ok(());
+        }
+
+        copy_named_assets(
+            asset_index,
+            launcher_paths,
+            &launcher_paths.build_virtual_assets_path(asset_index_id),
+        )
+        .await
+    }
 
-            let _ = load_file_or_download(file_path, download_url)
+    /// Versions whose asset index maps to resources read them from the
+    /// `resources` folder of the game directory, so each instance gets a copy.
+    pub async fn copy_resources_to_instance(
+        &self,
+        launcher_paths: &LauncherPaths,
+        instance: &Instance,
+    ) -> Result<()> {
+        match &self.asset_index {
+            Some(asset_index) if asset_index.map_to_resources => {
+                copy_named_assets(
+                    asset_index,
+                    launcher_paths,
+                    &instance.resources_dir_path(launcher_paths),
+                )
                 .await
-                .unwrap();
-        });
+            }
+            _ => Ok(()),
+        }
+    }
 
-        let stream = futures::stream::iter(futures).buffer_unordered(10);
+    fn plan_logging_config(
+        plan: &mut DownloadPlan,
+        launcher_paths: &LauncherPaths,
+        mojang_version: &MojangVersion,
+    ) {
+        if let Some(logging) = mojang_version.client_logging() {
+            plan.add(DownloadTask {
+                kind: DownloadKind::LoggingConfig,
+                path: launcher_paths.build_log_config_path(&logging.file.id),
+                url: logging.file.url.clone(),
+                sha1: Some(logging.file.sha1.clone()),
+                size: Some(logging.file.size),
+            });
+        }
+    }
 
-        let _ = stream.collect::<Vec<_>>().await;
+    /// Points the launch at the downloaded log4j config. Versions whose
+    /// bundled log4j cannot be protected with a JVM flag get a patched copy
+    /// instead.
+    async fn prepare_logging_config(
+        &mut self,
+        launcher_paths: &LauncherPaths,
+        mojang_version: &MojangVersion,
+    ) -> Result<()> {
+        let logging = match mojang_version.client_logging() {
+            Some(logging) => logging,
+            None => return Ok(()),
+        };
+
+        let config_path = launcher_paths.build_log_config_path(&logging.file.id);
+
+        let mitigation = Log4jMitigation::for_version(mojang_version);
+        let config_path = if mitigation.patches_config() {
+            let config_content = tokio::fs::read_to_string(&config_path).await?;
+            let patched_config_path =
+                launcher_paths.build_log_config_path(&format!("patched-{}", logging.file.id));
+            let patched_config = mitigation.patch_config(&config_content);
+            save_file(patched_config_path.clone(), patched_config.into_bytes()).await?;
+            patched_config_path
+        } else {
+            config_path
+        };
+
+        self.logging_config_path = Some(config_path);
 
         Ok(())
     }
@@ -181,9 +419,7 @@ impl LauncherVersion {
 
         for library in native_libraries {
             let library_path = launcher_paths.build_library_path(&library.get_path());
-            let download_url = library.url.clone();
-
-            let library_content = load_file_or_download(library_path, download_url).await?;
+            let library_content = tokio::fs::read(library_path).await?;
 
             zip_extract::extract(Cursor::new(library_content), &target_dir, true)?;
         }
@@ -191,34 +427,284 @@ impl LauncherVersion {
         Ok(())
     }
 
-    async fn download_runtime(
+    async fn download_runtime_manifest(
         &self,
         launcher_paths: &LauncherPaths,
         mojang_version: &MojangVersion,
         platform_data: &PlatformData,
-    ) -> Result<()> {
+    ) -> Result<RuntimeManifest> {
         let index_file_path = launcher_paths.get_path(LauncherPath::RuntimesIndex);
         let download_url = JRE_RUNTIMES_URL.to_string();
 
         let index: RuntimeIndex =
-            load_file_or_download_serialized(index_file_path, download_url).await?;
+            load_file_or_download_serialized(index_file_path, download_url, None, None).await?;
 
         let runtime_name = mojang_version.java_version.component.clone();
         let runtime_manifest_url = index
-            .select_entry(&platform_data, runtime_name.clone())
+            .select_entry(platform_data, runtime_name.clone())
             .ok_or(crate::Error::RuntimeNotFound(runtime_name.clone()))?
             .url
             .clone();
 
-        let runtime_manifest_path =
-            launcher_paths.build_runtime_manifest_path(&runtime_name.clone());
-        let runtime_manifest_content: RuntimeManifest =
-            load_file_or_download_serialized(runtime_manifest_path, runtime_manifest_url).await?;
+        let runtime_manifest_path = launcher_paths.build_runtime_manifest_path(&runtime_name);
 
-        runtime_manifest_content
-            .download(launcher_paths.build_runtime_path(&runtime_name))
-            .await?;
+        load_file_or_download_serialized(runtime_manifest_path, runtime_manifest_url, None, None)
+            .await
+    }
+}
 
-        Ok(())
+async fn copy_named_assets(
+    asset_index: &MojangAssetIndexFile,
+    launcher_paths: &LauncherPaths,
+    target_dir: &str,
+) -> Result<()> {
+    let assets_objects_base_folder = launcher_paths.get_path(LauncherPath::AssetsObjects);
+
+    for (object_name, object) in &asset_index.objects {
+        let object_path = object.build_file_path(&assets_objects_base_folder);
+        let target_path = MojangAssetIndexFile::build_named_file_path(target_dir, object_name);
+
+        let is_up_to_date = tokio::fs::metadata(&target_path)
+            .await
+            .is_ok_and(|metadata| metadata.len() == object.size);
+        if is_up_to_date {
+            continue;
+        }
+
+        create_parent_folders(target_path.clone()).await?;
+        tokio::fs::copy(&object_path, &target_path).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::model::mojang::{MojangAssetIndexFileObject, MojangVersionManifestLatest};
+
+    fn write_version(launcher_paths: &LauncherPaths, content: &serde_json::Value) {
+        let path = launcher_paths.build_version_file_path(content["id"].as_str().unwrap());
+        std::fs::create_dir_all(PathBuf::from(&path).parent().unwrap()).unwrap();
+        std::fs::write(path, content.to_string()).unwrap();
+    }
+
+    fn test_setup(name: &str) -> (LauncherPaths, MojangVersionManifest) {
+        let base_path = std::env::temp_dir().join(format!(
+            "minecraft-launcher-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&base_path);
+        let version_manifest = MojangVersionManifest {
+            latest: MojangVersionManifestLatest {
+                release: "1.20.1".to_string(),
+                snapshot: "1.20.1".to_string(),
+            },
+            versions: Vec::new(),
+        };
+
+        (
+            LauncherPaths::new(base_path.to_string_lossy().to_string()),
+            version_manifest,
+        )
+    }
+
+    #[tokio::test]
+    async fn resolves_multi_level_inheritance() {
+        let (launcher_paths, version_manifest) = test_setup("inheritance");
+        write_version(
+            &launcher_paths,
+            &json!({
+                "id": "1.20.1",
+                "assets": "5",
+                "assetIndex": { "id": "5", "sha1": "a", "size": 1, "totalSize": 1, "url": "" },
+                "downloads": {},
+                "javaVersion": { "component": "java-runtime-gamma", "majorVersion": 17 },
+                "mainClass": "net.minecraft.client.main.Main",
+                "libraries": [{ "name": "org.ow2.asm:asm:9.3" }]
+            }),
+        );
+        write_version(
+            &launcher_paths,
+            &json!({
+                "id": "1.20.1-forge-47.2.0",
+                "inheritsFrom": "1.20.1",
+                "mainClass": "cpw.mods.bootstraplauncher.BootstrapLauncher",
+                "libraries": [{ "name": "org.ow2.asm:asm:9.5" }]
+            }),
+        );
+        let optifine = json!({
+            "id": "1.20.1-OptiFine",
+            "inheritsFrom": "1.20.1-forge-47.2.0",
+            "libraries": [{ "name": "optifine:OptiFine:1.20.1_HD_U_I6" }]
+        });
+
+        let mojang_version = LauncherVersion::resolve_version_content(
+            &launcher_paths,
+            &version_manifest,
+            optifine,
+            vec!["1.20.1-OptiFine".to_string()],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            mojang_version.main_class,
+            "cpw.mods.bootstraplauncher.BootstrapLauncher"
+        );
+        assert_eq!(mojang_version.jar.as_deref(), Some("1.20.1"));
+        assert_eq!(mojang_version.java_version.major_version, 17);
+        let libraries: Vec<_> = mojang_version
+            .libraries
+            .iter()
+            .map(|library| library.name.as_str())
+            .collect();
+        assert_eq!(
+            libraries,
+            vec!["optifine:OptiFine:1.20.1_HD_U_I6", "org.ow2.asm:asm:9.5"]
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_inheritance_cycles() {
+        let (launcher_paths, version_manifest) = test_setup("inheritance-cycle");
+        write_version(&launcher_paths, &json!({ "id": "a", "inheritsFrom": "b" }));
+        write_version(&launcher_paths, &json!({ "id": "b", "inheritsFrom": "a" }));
+
+        let result = LauncherVersion::resolve_version_content(
+            &launcher_paths,
+            &version_manifest,
+            json!({ "id": "a", "inheritsFrom": "b" }),
+            vec!["a".to_string()],
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(crate::Error::VersionInheritanceCycle(version_id)) if version_id == "a"
+        ));
+    }
+
+    #[test]
+    fn skips_libraries_without_url() {
+        let (launcher_paths, _) = test_setup("plan-libraries");
+        let libraries = vec![
+            VersionLibrary::new(
+                "org/ow2/asm/asm/9.5/asm-9.5.jar".to_string(),
+                None,
+                None,
+                "https://libraries.minecraft.net/org/ow2/asm/asm/9.5/asm-9.5.jar".to_string(),
+                false,
+            ),
+            VersionLibrary::new(
+                "net/minecraftforge/forge/1.20.1-47.2.0/forge-1.20.1-47.2.0-client.jar".to_string(),
+                None,
+                None,
+                String::new(),
+                false,
+            ),
+        ];
+
+        let mut plan = DownloadPlan::default();
+        LauncherVersion::plan_libraries(&mut plan, &launcher_paths, &libraries);
+
+        assert_eq!(plan.tasks.len(), 1);
+        assert!(plan.tasks[0].path.ends_with("asm-9.5.jar"));
+    }
+
+    fn write_asset_object(launcher_paths: &LauncherPaths, hash: &str, content: &str) {
+        let path = MojangAssetIndexFileObject {
+            hash: hash.to_string(),
+            size: content.len() as u64,
+        }
+        .build_file_path(&launcher_paths.get_path(LauncherPath::AssetsObjects));
+        std::fs::create_dir_all(PathBuf::from(&path).parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn legacy_asset_index(virtual_index: bool, map_to_resources: bool) -> MojangAssetIndexFile {
+        serde_json::from_value(json!({
+            "virtual": virtual_index,
+            "map_to_resources": map_to_resources,
+            "objects": {
+                "sounds/random/click.ogg": { "hash": "4d3c8a0b", "size": 5 },
+                "lang/en_US.lang": { "hash": "9a7f21ce", "size": 4 }
+            }
+        }))
+        .unwrap()
+    }
+
+    fn manifest_version(id: &str) -> MojangVersionManifestVersion {
+        MojangVersionManifestVersion {
+            id: id.to_string(),
+            r#type: crate::model::VersionType::Release,
+            url: String::new(),
+            sha1: None,
+            time: String::new(),
+            release_time: String::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn copies_virtual_assets_by_name() {
+        let (launcher_paths, _) = test_setup("virtual-assets");
+        write_asset_object(&launcher_paths, "4d3c8a0b", "click");
+        write_asset_object(&launcher_paths, "9a7f21ce", "lang");
+        let launcher_version = LauncherVersion::new(manifest_version("1.6.4"));
+
+        launcher_version
+            .reconstruct_legacy_assets("legacy", &legacy_asset_index(true, false), &launcher_paths)
+            .await
+            .unwrap();
+
+        let virtual_dir = PathBuf::from(launcher_paths.build_virtual_assets_path("legacy"));
+        assert_eq!(
+            std::fs::read_to_string(virtual_dir.join("sounds/random/click.ogg")).unwrap(),
+            "click"
+        );
+        assert_eq!(
+            std::fs::read_to_string(virtual_dir.join("lang/en_US.lang")).unwrap(),
+            "lang"
+        );
+    }
+
+    #[tokio::test]
+    async fn copies_resources_into_instance() {
+        let (launcher_paths, _) = test_setup("resources-assets");
+        write_asset_object(&launcher_paths, "4d3c8a0b", "click");
+        write_asset_object(&launcher_paths, "9a7f21ce", "lang");
+        let mut launcher_version = LauncherVersion::new(manifest_version("1.5.2"));
+        let asset_index = legacy_asset_index(false, true);
+        launcher_version
+            .reconstruct_legacy_assets("pre-1.6", &asset_index, &launcher_paths)
+            .await
+            .unwrap();
+        launcher_version.asset_index = Some(asset_index);
+        let instance = Instance {
+            id: "b1a2c3".to_string(),
+            name: "Beta".to_string(),
+            version_id: "1.5.2".to_string(),
+            mod_loader: None,
+            settings: Default::default(),
+            installed_version_id: None,
+            game_directory: None,
+            launcher_profile_key: None,
+            launcher_profile_latest: None,
+        };
+
+        launcher_version
+            .copy_resources_to_instance(&launcher_paths, &instance)
+            .await
+            .unwrap();
+
+        let resources_dir = PathBuf::from(instance.resources_dir_path(&launcher_paths));
+        assert_eq!(
+            std::fs::read_to_string(resources_dir.join("sounds/random/click.ogg")).unwrap(),
+            "click"
+        );
+        assert!(!PathBuf::from(launcher_paths.build_virtual_assets_path("pre-1.6")).exists());
     }
 }
@@ -0,0 +1,30 @@
+pub use crate::error::Error;
+pub use launcher_paths::{LauncherPath, LauncherPaths};
+pub use tokio_util::sync::CancellationToken;
+
+pub mod accounts;
+pub mod auth;
+mod channel;
+pub mod config;
+pub mod download_plan;
+pub mod download_progress;
+pub mod error;
+pub mod files;
+pub mod game_exit;
+pub mod game_log;
+pub mod http_client;
+pub mod instances;
+pub mod jvm_settings;
+pub mod launch_hooks;
+pub mod launch_options;
+pub mod launch_placeholders;
+pub mod launcher;
+pub mod launcher_paths;
+pub mod launcher_runner;
+pub mod launcher_version;
+pub mod loaders;
+pub mod logging_config;
+pub mod model;
+pub mod platform;
+
+pub type Result<T> = std::result::Result<T, Error>;
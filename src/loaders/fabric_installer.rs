@@ -0,0 +1,91 @@
+use crate::{
+    files::save_file,
+    http_client,
+    model::{fabric::FabricLoaderVersionEntry, mojang::MojangVersionProfile},
+    LauncherPaths, Result,
+};
+
+const FABRIC_META_URL: &str = "https://meta.fabricmc.net/v2";
+const QUILT_META_URL: &str = "https://meta.quiltmc.org/v3";
+
+/// Installs loaders served by Fabric's meta API. Quilt exposes the same
+/// endpoints and profiles, so it goes through this installer as well.
+pub struct FabricInstaller {
+    meta_url: String,
+}
+
+impl Default for FabricInstaller {
+    fn default() -> Self {
+        Self::fabric()
+    }
+}
+
+impl FabricInstaller {
+    pub fn new(meta_url: String) -> Self {
+        Self { meta_url }
+    }
+
+    pub fn fabric() -> Self {
+        Self::new(FABRIC_META_URL.to_string())
+    }
+
+    pub fn quilt() -> Self {
+        Self::new(QUILT_META_URL.to_string())
+    }
+
+    pub async fn list_loader_versions(
+        &self,
+        game_version: &str,
+    ) -> Result<Vec<FabricLoaderVersionEntry>> {
+        let url = format!("{}/versions/loader/{}", self.meta_url, game_version);
+
+        http_client::download_serialized(url).await
+    }
+
+    /// Writes the loader profile into `versions/` and returns its version id,
+    /// ready to be passed to `Launcher::select_version`. Without a loader
+    /// version, the latest stable one is picked.
+    ///
+    /// Fabric and Quilt profiles are saved unmerged, with `inheritsFrom`
+    /// pointing at the game version. `LauncherVersion::start_downloads`
+    /// merges them with their parent when the version is selected, the same
+    /// way the official launcher does, so the game version JSON is never
+    /// duplicated into the loader profile. Saving a merged profile would get
+    /// its arguments appended to the parent ones a second time.
+    pub async fn install(
+        &self,
+        launcher_paths: &LauncherPaths,
+        game_version: &str,
+        loader_version: Option<&str>,
+    ) -> Result<String> {
+        let loader_version = match loader_version {
+            Some(loader_version) => loader_version.to_string(),
+            None => self.find_latest_loader_version(game_version).await?,
+        };
+
+        let url = format!(
+            "{}/versions/loader/{}/{}/profile/json",
+            self.meta_url, game_version, loader_version
+        );
+        let profile_content = http_client::download(url).await?;
+        let profile: MojangVersionProfile = serde_json::from_slice(&profile_content)?;
+
+        let version_file_path = launcher_paths.build_version_file_path(&profile.id);
+        save_file(version_file_path, profile_content).await?;
+
+        Ok(profile.id)
+    }
+
+    async fn find_latest_loader_version(&self, game_version: &str) -> Result<String> {
+        let loader_versions = self.list_loader_versions(game_version).await?;
+
+        loader_versions
+            .iter()
+            .find(|entry| entry.loader.is_stable())
+            .or(loader_versions.first())
+            .map(|entry| entry.loader.version.clone())
+            .ok_or(crate::Error::LoaderVersionNotFound(
+                game_version.to_string(),
+            ))
+    }
+}
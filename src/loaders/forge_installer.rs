@@ -0,0 +1,556 @@
+use std::{
+    collections::HashMap,
+    io::{Cursor, Read},
+    path::Path,
+};
+
+use tokio::process::Command;
+use tokio_util::sync::CancellationToken;
+use zip::ZipArchive;
+
+use crate::{
+    download_plan::{DownloadContext, DownloadKind, DownloadPlan, DownloadTask},
+    download_progress::{DownloadEvent, DownloadStage},
+    files::{compute_file_sha1, load_file_or_download, save_file},
+    launcher_paths::join_paths,
+    launcher_runner::get_java_executable_path,
+    launcher_version::LauncherVersion,
+    model::{
+        forge::{ForgeInstallProcessor, ForgeInstallProfile},
+        mojang::{MojangVersionManifest, MojangVersionProfile},
+        MavenArtifact,
+    },
+    platform::PlatformData,
+    LauncherPath, LauncherPaths, Result,
+};
+
+const FORGE_MAVEN_URL: &str = "https://maven.minecraftforge.net";
+const FORGE_ARTIFACT: &str = "net.minecraftforge:forge";
+const NEOFORGE_MAVEN_URL: &str = "https://maven.neoforged.net/releases";
+const NEOFORGE_ARTIFACT: &str = "net.neoforged:neoforge";
+
+const INSTALL_PROFILE_ENTRY: &str = "install_profile.json";
+const DEFAULT_VERSION_JSON_ENTRY: &str = "version.json";
+const EMBEDDED_MAVEN_PREFIX: &str = "maven/";
+
+/// Runs the installers of Forge and NeoForge, which share the same
+/// `install_profile.json` format and processor chain.
+pub struct ForgeInstaller {
+    maven_url: String,
+    artifact: String,
+}
+
+impl ForgeInstaller {
+    pub fn new(maven_url: String, artifact: String) -> Self {
+        Self {
+            maven_url,
+            artifact,
+        }
+    }
+
+    pub fn forge() -> Self {
+        Self::new(FORGE_MAVEN_URL.to_string(), FORGE_ARTIFACT.to_string())
+    }
+
+    pub fn neoforge() -> Self {
+        Self::new(
+            NEOFORGE_MAVEN_URL.to_string(),
+            NEOFORGE_ARTIFACT.to_string(),
+        )
+    }
+
+    /// Installs the given loader version, as written in its Maven coordinates
+    /// (`1.20.1-47.2.0` for Forge, `21.1.77` for NeoForge), and returns the id
+    /// of the resulting version.
+    pub async fn install(
+        &self,
+        launcher_paths: &LauncherPaths,
+        platform_data: &PlatformData,
+        version_manifest: &MojangVersionManifest,
+        loader_version: &str,
+        context: DownloadContext<'_>,
+    ) -> Result<String> {
+        let installer_artifact =
+            MavenArtifact::parse(&format!("{}:{}:installer", self.artifact, loader_version))
+                .ok_or(crate::Error::LoaderVersionNotFound(
+                    loader_version.to_string(),
+                ))?;
+        let installer_path = launcher_paths.build_library_path(&installer_artifact.path());
+        let installer_content = load_file_or_download(
+            installer_path.clone(),
+            installer_artifact.build_download_url(&self.maven_url),
+            None,
+            None,
+        )
+        .await?;
+        let mut installer = ZipArchive::new(Cursor::new(installer_content))?;
+
+        // Legacy installers have a differently shaped `install_profile.json`
+        // that does not parse as the processor based one.
+        let install_profile: ForgeInstallProfile =
+            read_zip_entry(&mut installer, INSTALL_PROFILE_ENTRY)
+                .ok()
+                .and_then(|content| serde_json::from_slice(&content).ok())
+                .ok_or(crate::Error::UnsupportedInstaller(
+                    loader_version.to_string(),
+                ))?;
+        let version_json_entry = install_profile
+            .json
+            .as_deref()
+            .unwrap_or(DEFAULT_VERSION_JSON_ENTRY)
+            .trim_start_matches('/');
+        let version_content = read_zip_entry(&mut installer, version_json_entry)?;
+        let version_profile: MojangVersionProfile = serde_json::from_slice(&version_content)?;
+
+        extract_embedded_libraries(&mut installer, launcher_paths).await?;
+        download_install_libraries(&install_profile, launcher_paths, platform_data, context)
+            .await?;
+
+        let parent_manifest_version = version_manifest
+            .find_version_by_id(install_profile.minecraft.clone())
+            .ok_or(crate::Error::VersionNotFound(
+                install_profile.minecraft.clone(),
+            ))?;
+        let mut parent_version = LauncherVersion::new(parent_manifest_version);
+        let parent_mojang_version = parent_version
+            .download_client_and_runtime(launcher_paths, platform_data, version_manifest, context)
+            .await?;
+
+        let java_path = get_java_executable_path(
+            platform_data,
+            launcher_paths.build_runtime_path(&parent_mojang_version.java_version.component),
+        );
+        let processor_runner = ProcessorRunner {
+            launcher_paths,
+            platform_data,
+            java_path,
+            cancellation: context.cancellation,
+            variables: build_processor_variables(
+                &install_profile,
+                &mut installer,
+                launcher_paths,
+                &installer_path,
+                parent_mojang_version.client_jar_id(),
+            )
+            .await?,
+        };
+
+        let processors: Vec<&ForgeInstallProcessor> = install_profile
+            .processors
+            .iter()
+            .filter(|processor| processor.runs_on_client())
+            .collect();
+
+        context
+            .progress
+            .on_event(DownloadEvent::StageStarted(DownloadStage::Processors));
+        for (index, processor) in processors.iter().enumerate() {
+            if context.cancellation.is_cancelled() {
+                return Err(crate::Error::Cancelled);
+            }
+
+            context.progress.on_event(DownloadEvent::ProcessorStarted {
+                jar: processor.jar.clone(),
+                index,
+                total: processors.len(),
+            });
+            processor_runner.run(processor).await?;
+        }
+
+        let version_file_path = launcher_paths.build_version_file_path(&version_profile.id);
+        save_file(version_file_path, version_content).await?;
+
+        Ok(version_profile.id)
+    }
+}
+
+struct ProcessorRunner<'a> {
+    launcher_paths: &'a LauncherPaths,
+    platform_data: &'a PlatformData,
+    java_path: String,
+    cancellation: &'a CancellationToken,
+    variables: HashMap<String, String>,
+}
+
+impl ProcessorRunner<'_> {
+    async fn run(&self, processor: &ForgeInstallProcessor) -> Result<()> {
+        if !processor.outputs.is_empty() && self.verify_outputs(processor).await.is_ok() {
+            return Ok(());
+        }
+
+        let jar_path = self.library_path(&processor.jar)?;
+        let main_class =
+            read_main_class(&jar_path)
+                .await?
+                .ok_or(crate::Error::InstallerProcessorFailed(
+                    processor.jar.clone(),
+                ))?;
+
+        let mut classpath = vec![jar_path];
+        for library in &processor.classpath {
+            classpath.push(self.library_path(library)?);
+        }
+
+        let arguments = processor
+            .args
+            .iter()
+            .map(|argument| self.resolve_argument(argument))
+            .collect::<Result<Vec<String>>>()?;
+
+        let mut child = Command::new(&self.java_path)
+            .arg("-cp")
+            .arg(classpath.join(self.platform_data.platform_type.classpath_separator()))
+            .arg(main_class)
+            .args(arguments)
+            .kill_on_drop(true)
+            .spawn()?;
+
+        let status = tokio::select! {
+            status = child.wait() => status?,
+            _ = self.cancellation.cancelled() => {
+                child.kill().await?;
+                return Err(crate::Error::Cancelled);
+            }
+        };
+
+        if !status.success() {
+            return Err(crate::Error::InstallerProcessorFailed(
+                processor.jar.clone(),
+            ));
+        }
+
+        self.verify_outputs(processor).await
+    }
+
+    /// Checks every output file against the SHA-1 declared by the profile.
+    async fn verify_outputs(&self, processor: &ForgeInstallProcessor) -> Result<()> {
+        for (output, expected_sha1) in &processor.outputs {
+            let path = self.resolve_argument(output)?;
+            let expected = self.resolve_argument(expected_sha1)?;
+
+            let actual = if Path::new(&path).exists() {
+                compute_file_sha1(&path).await?
+            } else {
+                String::new()
+            };
+
+            if !actual.eq_ignore_ascii_case(&expected) {
+                return Err(crate::Error::FileHashMismatch {
+                    path,
+                    expected,
+                    actual,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Arguments are either a `{VARIABLE}`, a `[maven:coordinates]` or a
+    /// literal value.
+    fn resolve_argument(&self, argument: &str) -> Result<String> {
+        if let Some(name) = argument
+            .strip_prefix('{')
+            .and_then(|argument| argument.strip_suffix('}'))
+        {
+            return self
+                .variables
+                .get(name)
+                .cloned()
+                .ok_or(crate::Error::InstallerProcessorFailed(argument.to_string()));
+        }
+
+        if let Some(coordinates) = argument
+            .strip_prefix('[')
+            .and_then(|argument| argument.strip_suffix(']'))
+        {
+            return self.library_path(coordinates);
+        }
+
+        Ok(argument.to_string())
+    }
+
+    fn library_path(&self, coordinates: &str) -> Result<String> {
+        build_maven_library_path(self.launcher_paths, coordinates)
+    }
+}
+
+async fn build_processor_variables(
+    install_profile: &ForgeInstallProfile,
+    installer: &mut ZipArchive<Cursor<Vec<u8>>>,
+    launcher_paths: &LauncherPaths,
+    installer_path: &str,
+    client_jar_id: &str,
+) -> Result<HashMap<String, String>> {
+    let extraction_dir = join_paths(
+        launcher_paths.get_path(LauncherPath::TempDir),
+        vec!["installers", &install_profile.version],
+    );
+
+    let mut variables = HashMap::new();
+    variables.insert("SIDE".to_string(), "client".to_string());
+    variables.insert(
+        "MINECRAFT_JAR".to_string(),
+        launcher_paths.build_client_file_path(client_jar_id),
+    );
+    variables.insert(
+        "MINECRAFT_VERSION".to_string(),
+        install_profile.minecraft.clone(),
+    );
+    variables.insert("ROOT".to_string(), launcher_paths.base_path.clone());
+    variables.insert("INSTALLER".to_string(), installer_path.to_string());
+    variables.insert(
+        "LIBRARY_DIR".to_string(),
+        launcher_paths.get_path(LauncherPath::LibrariesDir),
+    );
+
+    for (name, data) in &install_profile.data {
+        let value = &data.client;
+
+        let resolved_value = if let Some(coordinates) = value
+            .strip_prefix('[')
+            .and_then(|value| value.strip_suffix(']'))
+        {
+            build_maven_library_path(launcher_paths, coordinates)?
+        } else if let Some(literal) = value
+            .strip_prefix('\'')
+            .and_then(|value| value.strip_suffix('\''))
+        {
+            literal.to_string()
+        } else if let Some(entry_name) = value.strip_prefix('/') {
+            let entry_path = join_paths(extraction_dir.clone(), entry_name.split('/').collect());
+            save_file(entry_path.clone(), read_zip_entry(installer, entry_name)?).await?;
+            entry_path
+        } else {
+            value.clone()
+        };
+
+        variables.insert(name.clone(), resolved_value);
+    }
+
+    Ok(variables)
+}
+
+/// Installers bundle the libraries that are not published on a Maven
+/// repository under `maven/`.
+async fn extract_embedded_libraries(
+    installer: &mut ZipArchive<Cursor<Vec<u8>>>,
+    launcher_paths: &LauncherPaths,
+) -> Result<()> {
+    let entry_names: Vec<String> = installer
+        .file_names()
+        .filter(|name| name.starts_with(EMBEDDED_MAVEN_PREFIX) && !name.ends_with('/'))
+        .map(|name| name.to_string())
+        .collect();
+
+    for entry_name in entry_names {
+        let library_path = entry_name.trim_start_matches(EMBEDDED_MAVEN_PREFIX);
+        let path = join_paths(
+            launcher_paths.get_path(LauncherPath::LibrariesDir),
+            library_path.split('/').collect(),
+        );
+
+        save_file(path, read_zip_entry(installer, &entry_name)?).await?;
+    }
+
+    Ok(())
+}
+
+async fn download_install_libraries(
+    install_profile: &ForgeInstallProfile,
+    launcher_paths: &LauncherPaths,
+    platform_data: &PlatformData,
+    context: DownloadContext<'_>,
+) -> Result<()> {
+    let mut plan = DownloadPlan::default();
+
+    for library in &install_profile.libraries {
+        let library = library.into_version_library(platform_data)?;
+
+        // Libraries without an url are bundled in the installer or
+        // produced by the processors.
+        if library.url.is_empty() {
+            continue;
+        }
+
+        plan.add(DownloadTask {
+            kind: DownloadKind::Library,
+            path: launcher_paths.build_library_path(&library.get_path()),
+            url: library.url,
+            sha1: library.sha1,
+            size: library.size,
+        });
+    }
+
+    plan.execute(context).await
+}
+
+fn build_maven_library_path(launcher_paths: &LauncherPaths, coordinates: &str) -> Result<String> {
+    let artifact = MavenArtifact::parse(coordinates).ok_or(
+        crate::Error::LibraryDownloadNotFound(coordinates.to_string()),
+    )?;
+
+    Ok(join_paths(
+        launcher_paths.get_path(LauncherPath::LibrariesDir),
+        artifact.path().split('/').collect(),
+    ))
+}
+
+fn read_zip_entry(archive: &mut ZipArchive<Cursor<Vec<u8>>>, name: &str) -> Result<Vec<u8>> {
+    let mut entry = archive.by_name(name)?;
+    let mut content = Vec::new();
+    entry.read_to_end(&mut content)?;
+
+    Ok(content)
+}
+
+async fn read_main_class(jar_path: &str) -> Result<Option<String>> {
+    if !Path::new(jar_path).exists() {
+        return Err(crate::Error::LibraryDownloadNotFound(jar_path.to_string()));
+    }
+
+    let jar_content = tokio::fs::read(jar_path).await?;
+    let mut jar = ZipArchive::new(Cursor::new(jar_content))?;
+    let manifest =
+        String::from_utf8_lossy(&read_zip_entry(&mut jar, "META-INF/MANIFEST.MF")?).to_string();
+
+    Ok(manifest
+        .lines()
+        .find_map(|line| line.strip_prefix("Main-Class:"))
+        .map(|main_class| main_class.trim().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use zip::{write::SimpleFileOptions, ZipWriter};
+
+    use super::*;
+    use crate::platform::{Arch, PlatformType};
+
+    fn test_setup(name: &str) -> LauncherPaths {
+        let base_path = std::env::temp_dir().join(format!(
+            "minecraft-launcher-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&base_path);
+
+        LauncherPaths::new(base_path.to_string_lossy().to_string())
+    }
+
+    fn build_installer(entries: &[(&str, &[u8])]) -> ZipArchive<Cursor<Vec<u8>>> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            writer
+                .start_file(name.to_string(), SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content).unwrap();
+        }
+
+        ZipArchive::new(writer.finish().unwrap()).unwrap()
+    }
+
+    fn install_profile(data: serde_json::Value) -> ForgeInstallProfile {
+        serde_json::from_value(serde_json::json!({
+            "version": "1.20.1-forge-47.2.0",
+            "minecraft": "1.20.1",
+            "data": data,
+        }))
+        .unwrap()
+    }
+
+    fn library_path(launcher_paths: &LauncherPaths, path: &str) -> String {
+        join_paths(
+            launcher_paths.get_path(LauncherPath::LibrariesDir),
+            path.split('/').collect(),
+        )
+    }
+
+    #[tokio::test]
+    async fn resolves_processor_variables() {
+        let launcher_paths = test_setup("forge-variables");
+        let mut installer = build_installer(&[("data/client.lzma", b"patches")]);
+        let install_profile = install_profile(serde_json::json!({
+            "MAPPINGS": {
+                "client": "[de.oceanlabs.mcp:mcp_config:1.20.1@zip]",
+                "server": ""
+            },
+            "BINPATCH": { "client": "/data/client.lzma", "server": "" },
+            "MC_SLIM_SHA": { "client": "'a1b2c3'", "server": "" }
+        }));
+
+        let variables = build_processor_variables(
+            &install_profile,
+            &mut installer,
+            &launcher_paths,
+            "forge-installer.jar",
+            "1.20.1",
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(variables["SIDE"], "client");
+        assert_eq!(variables["INSTALLER"], "forge-installer.jar");
+        assert_eq!(
+            variables["MINECRAFT_JAR"],
+            launcher_paths.build_client_file_path("1.20.1")
+        );
+        assert_eq!(
+            variables["MAPPINGS"],
+            library_path(
+                &launcher_paths,
+                "de/oceanlabs/mcp/mcp_config/1.20.1/mcp_config-1.20.1.zip"
+            )
+        );
+        assert_eq!(variables["MC_SLIM_SHA"], "a1b2c3");
+
+        let binpatch_path = join_paths(
+            launcher_paths.get_path(LauncherPath::TempDir),
+            vec!["installers", "1.20.1-forge-47.2.0", "data", "client.lzma"],
+        );
+        assert_eq!(variables["BINPATCH"], binpatch_path);
+        assert_eq!(std::fs::read(binpatch_path).unwrap(), b"patches");
+    }
+
+    #[test]
+    fn resolves_processor_arguments() {
+        let launcher_paths = test_setup("forge-arguments");
+        let platform_data = PlatformData {
+            platform_type: PlatformType::Linux,
+            arch: Arch::X86_64,
+            os_version: String::new(),
+        };
+        let cancellation = CancellationToken::new();
+        let processor_runner = ProcessorRunner {
+            launcher_paths: &launcher_paths,
+            platform_data: &platform_data,
+            java_path: "java".to_string(),
+            cancellation: &cancellation,
+            variables: HashMap::from([("SIDE".to_string(), "client".to_string())]),
+        };
+
+        assert_eq!(
+            processor_runner.resolve_argument("{SIDE}").unwrap(),
+            "client"
+        );
+        assert_eq!(
+            processor_runner
+                .resolve_argument("[net.minecraftforge:installertools:1.3.0:fatjar]")
+                .unwrap(),
+            library_path(
+                &launcher_paths,
+                "net/minecraftforge/installertools/1.3.0/installertools-1.3.0-fatjar.jar"
+            )
+        );
+        assert_eq!(
+            processor_runner.resolve_argument("--task").unwrap(),
+            "--task"
+        );
+        assert!(matches!(
+            processor_runner.resolve_argument("{MISSING}"),
+            Err(crate::Error::InstallerProcessorFailed(_))
+        ));
+    }
+}
@@ -0,0 +1,5 @@
+mod fabric_installer;
+mod forge_installer;
+
+pub use fabric_installer::*;
+pub use forge_installer::*;
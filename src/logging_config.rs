@@ -0,0 +1,191 @@
+use crate::model::{mojang::MojangVersion, MavenArtifact};
+
+const LOG4J_CORE_LIBRARY: &str = "org.apache.logging.log4j:log4j-core";
+const DISABLE_LOOKUPS_JVM_ARGUMENT: &str = "-Dlog4j2.formatMsgNoLookups=true";
+const LOOKUP_REGEX_FILTER: &str =
+    r#"<RegexFilter regex="(?s).*\$\{[^}]*\}.*" onMatch="DENY" onMismatch="NEUTRAL"/>"#;
+
+/// How a version is protected against Log4Shell (CVE-2021-44228), depending
+/// on the log4j release it bundles.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Log4jMitigation {
+    /// log4j 2.15 and later, or no log4j at all.
+    None,
+    /// log4j 2.10 to 2.14 honour `log4j2.formatMsgNoLookups`.
+    DisableLookupsFlag,
+    /// log4j 2.7 to 2.9 support `%msg{nolookups}` in patterns.
+    NoLookupsPattern,
+    /// Older releases can only drop messages containing a lookup.
+    LookupRegexFilter,
+}
+
+impl Log4jMitigation {
+    pub fn for_version(mojang_version: &MojangVersion) -> Self {
+        let log4j_version = mojang_version
+            .libraries
+            .iter()
+            .filter_map(|library| MavenArtifact::parse(&library.name))
+            .find(|artifact| {
+                format!("{}:{}", artifact.group, artifact.artifact) == LOG4J_CORE_LIBRARY
+            })
+            .and_then(|artifact| parse_major_minor(&artifact.version));
+
+        match log4j_version {
+            Some((2, minor)) if minor < 7 => Log4jMitigation::LookupRegexFilter,
+            Some((2, minor)) if minor < 10 => Log4jMitigation::NoLookupsPattern,
+            Some((2, minor)) if minor < 15 => Log4jMitigation::DisableLookupsFlag,
+            _ => Log4jMitigation::None,
+        }
+    }
+
+    pub fn jvm_argument(&self) -> Option<&'static str> {
+        match self {
+            Log4jMitigation::DisableLookupsFlag => Some(DISABLE_LOOKUPS_JVM_ARGUMENT),
+            _ => None,
+        }
+    }
+
+    /// Whether the logging config has to be rewritten by `patch_config`.
+    pub fn patches_config(&self) -> bool {
+        matches!(
+            self,
+            Log4jMitigation::NoLookupsPattern | Log4jMitigation::LookupRegexFilter
+        )
+    }
+
+    pub fn patch_config(&self, config: &str) -> String {
+        match self {
+            Log4jMitigation::NoLookupsPattern => config
+                .replace("%msg{nolookups}", "%msg")
+                .replace("%msg", "%msg{nolookups}"),
+            Log4jMitigation::LookupRegexFilter => insert_configuration_filter(config),
+            _ => config.to_string(),
+        }
+    }
+}
+
+fn insert_configuration_filter(config: &str) -> String {
+    if config.contains(LOOKUP_REGEX_FILTER) {
+        return config.to_string();
+    }
+
+    let configuration_start = match config.find("<Configuration") {
+        Some(configuration_start) => configuration_start,
+        None => return config.to_string(),
+    };
+
+    match config[configuration_start..].find('>') {
+        Some(tag_end) => {
+            let insert_at = configuration_start + tag_end + 1;
+            format!(
+                "{}\n    {}{}",
+                &config[..insert_at],
+                LOOKUP_REGEX_FILTER,
+                &config[insert_at..]
+            )
+        }
+        None => config.to_string(),
+    }
+}
+
+fn parse_major_minor(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.split(|c: char| !c.is_ascii_digit());
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+
+    Some((major, minor))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn version_with_libraries(libraries: &[&str]) -> MojangVersion {
+        let libraries: Vec<serde_json::Value> = libraries
+            .iter()
+            .map(|name| json!({ "name": name }))
+            .collect();
+
+        serde_json::from_value(json!({
+            "id": "1.12.2",
+            "assets": "1.12",
+            "assetIndex": { "id": "1.12", "sha1": "a", "size": 1, "totalSize": 1, "url": "" },
+            "downloads": {},
+            "javaVersion": { "component": "jre-legacy", "majorVersion": 8 },
+            "mainClass": "net.minecraft.client.main.Main",
+            "libraries": libraries
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn picks_mitigation_by_log4j_version() {
+        let cases = [
+            ("2.0-beta9", Log4jMitigation::LookupRegexFilter),
+            ("2.6.2", Log4jMitigation::LookupRegexFilter),
+            ("2.7", Log4jMitigation::NoLookupsPattern),
+            ("2.9.1", Log4jMitigation::NoLookupsPattern),
+            ("2.10.0", Log4jMitigation::DisableLookupsFlag),
+            ("2.14.1", Log4jMitigation::DisableLookupsFlag),
+            ("2.15.0", Log4jMitigation::None),
+            ("2.17.0", Log4jMitigation::None),
+        ];
+
+        for (log4j_version, mitigation) in cases {
+            let mojang_version = version_with_libraries(&[
+                "org.apache.logging.log4j:log4j-api:2.17.0",
+                &format!("org.apache.logging.log4j:log4j-core:{}", log4j_version),
+            ]);
+
+            assert_eq!(
+                Log4jMitigation::for_version(&mojang_version),
+                mitigation,
+                "{}",
+                log4j_version
+            );
+        }
+
+        assert_eq!(
+            Log4jMitigation::for_version(&version_with_libraries(&["org.lwjgl:lwjgl:3.3.1"])),
+            Log4jMitigation::None
+        );
+    }
+
+    #[test]
+    fn inserts_lookup_filter_into_config() {
+        let config = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Configuration status="WARN" packages="com.mojang.util">
+    <Appenders/>
+</Configuration>"#;
+
+        let patched = Log4jMitigation::LookupRegexFilter.patch_config(config);
+
+        assert_eq!(
+            patched,
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<Configuration status="WARN" packages="com.mojang.util">
+    {}
+    <Appenders/>
+</Configuration>"#,
+                LOOKUP_REGEX_FILTER
+            )
+        );
+        assert_eq!(
+            Log4jMitigation::LookupRegexFilter.patch_config(&patched),
+            patched
+        );
+    }
+
+    #[test]
+    fn adds_nolookups_to_patterns() {
+        let config = r#"<PatternLayout pattern="[%d{HH:mm:ss}] %msg{nolookups}%n"/><PatternLayout pattern="%msg%n"/>"#;
+
+        assert_eq!(
+            Log4jMitigation::NoLookupsPattern.patch_config(config),
+            r#"<PatternLayout pattern="[%d{HH:mm:ss}] %msg{nolookups}%n"/><PatternLayout pattern="%msg{nolookups}%n"/>"#
+        );
+    }
+}
@@ -1,23 +1,17 @@
-use std::{env, fs};
-
-pub use crate::error::Error;
-use config::LauncherConfig;
-use launcher::Launcher;
-pub use launcher_paths::{LauncherPath, LauncherPaths};
-use platform::{PlatformData, PlatformType};
-
-mod config;
-mod error;
-mod files;
-mod http_client;
-mod launcher;
-mod launcher_paths;
-mod launcher_runner;
-mod launcher_version;
-mod model;
-mod platform;
-
-type Result<T> = std::result::Result<T, Error>;
+use std::{collections::HashMap, env, fs, io::Write, sync::Mutex};
+
+use minecraft_launcher::{
+    accounts::AccountStore,
+    auth::{AuthEndpoints, AuthSession, MicrosoftAuthenticator},
+    config::LauncherConfig,
+    download_progress::{DownloadEvent, DownloadProgress},
+    instances::{InstanceStore, ModLoader, ModLoaderKind},
+    launch_options::LaunchOptions,
+    launcher::Launcher,
+    launcher_paths,
+    platform::PlatformData,
+    CancellationToken, LauncherPaths, Result,
+};
 
 #[tokio::main]
 async fn main() {
@@ -32,17 +26,103 @@ async fn main() {
     let launcher_paths = create_launcher_paths(data_location);
 
     let platform_data = load_platform_data();
+    let progress_bar = CliProgressBar::default();
+    let cancellation = CancellationToken::new();
+    tokio::spawn(cancel_on_ctrl_c(cancellation.clone()));
     let mut launcher = start_launcher(platform_data, launcher_paths.clone()).await;
 
-    let selected_version = launcher.select_version(version_id).unwrap();
-    launcher.start_downloads().await.unwrap();
+    if let Ok(client_id) = env::var("MICROSOFT_CLIENT_ID") {
+        let authenticator = MicrosoftAuthenticator::new(client_id, AuthEndpoints::default());
+
+        if launcher.account_store.selected_account().is_none() {
+            let auth_session = login_microsoft(&authenticator).await.unwrap();
+            let account = launcher.account_store.add_microsoft_account(&auth_session);
+            launcher.account_store.select_account(&account.id).unwrap();
+        }
+
+        launcher.set_authenticator(authenticator);
+    }
+
+    let mod_loader = args.get(2).map(|loader| ModLoader {
+        kind: match loader.as_str() {
+            "fabric" => ModLoaderKind::Fabric,
+            "quilt" => ModLoaderKind::Quilt,
+            "forge" => ModLoaderKind::Forge,
+            "neoforge" => ModLoaderKind::NeoForge,
+            _ => panic!("Loader desconhecido: {}", loader),
+        },
+        version: args.get(3).cloned(),
+    });
+    let instance_name = args[1..].join(" ");
+    let instance_id = match launcher
+        .instance_store
+        .instances()
+        .iter()
+        .find(|instance| instance.name == instance_name)
+    {
+        Some(instance) => instance.id.clone(),
+        None => {
+            launcher
+                .instance_store
+                .create_instance(instance_name, version_id, mod_loader)
+                .await
+                .unwrap()
+                .id
+        }
+    };
+
+    let instance = launcher
+        .prepare_instance(&instance_id, &progress_bar, &cancellation)
+        .await
+        .unwrap();
+
+    println!("Selected instance: {:?}", instance);
 
-    println!("Selected version: {:?}", selected_version);
+    let game_exit = launcher
+        .launch_game(&instance, &LaunchOptions::default(), &cancellation)
+        .await
+        .unwrap();
 
-    launcher.launch_game().await.unwrap();
+    if let Some(crash_cause) = game_exit.crash_cause {
+        println!(
+            "Game crashed with exit code {:?} ({:?})",
+            game_exit.exit_code, crash_cause
+        );
+        for report in game_exit
+            .crash_reports
+            .iter()
+            .chain(&game_exit.jvm_crash_logs)
+        {
+            println!("See {}", report);
+        }
+    }
+
+    if let Some(error) = game_exit.post_exit_hook_error {
+        println!("Post-exit hook failed: {}", error);
+    }
 
     launcher.persist_config().await.unwrap();
-    ()
+    launcher.persist_accounts().await.unwrap();
+}
+
+async fn cancel_on_ctrl_c(cancellation: CancellationToken) {
+    if tokio::signal::ctrl_c().await.is_ok() {
+        println!("Cancelling...");
+        cancellation.cancel();
+    }
+}
+
+async fn login_microsoft(authenticator: &MicrosoftAuthenticator) -> Result<AuthSession> {
+    let device_code = authenticator.request_device_code().await?;
+    println!(
+        "To sign in, open {} and enter the code {}",
+        device_code.verification_uri, device_code.user_code
+    );
+
+    let auth_session = authenticator.login_with_device_code(&device_code).await?;
+    println!("Logged in as {}", auth_session.username);
+
+    Ok(auth_session)
 }
 
 pub fn load_platform_data() -> PlatformData {
@@ -68,12 +148,27 @@ fn create_launcher_paths(base_path: String) -> launcher_paths::LauncherPaths {
 
 async fn start_launcher(platform_data: PlatformData, launcher_paths: LauncherPaths) -> Launcher {
     let config = load_config(launcher_paths.clone()).await.unwrap();
-    let mut launcher = Launcher::new(platform_data, launcher_paths, config);
+    let account_store = AccountStore::load(launcher_paths.clone()).await.unwrap();
+    let instance_store = InstanceStore::load(launcher_paths.clone()).await.unwrap();
+    let mut launcher = Launcher::new(
+        platform_data,
+        launcher_paths,
+        config,
+        account_store,
+        instance_store,
+    );
 
     launcher.launcher_paths.create_folders().await.unwrap();
 
     launcher.load_version_manifest_or_download().await.unwrap();
 
+    if let Some(instance) = launcher.migrate_legacy_game_dir().await.unwrap() {
+        println!(
+            "Moved the shared game directory into the instance {:?}",
+            instance.name
+        );
+    }
+
     launcher
 }
 
@@ -83,3 +178,94 @@ async fn load_config(launcher_paths: LauncherPaths) -> Result<LauncherConfig> {
         .unwrap_or_default();
     Ok(config)
 }
+
+/// Draws the whole download as a single progress bar.
+#[derive(Default)]
+struct CliProgressBar {
+    state: Mutex<CliProgressState>,
+}
+
+#[derive(Default)]
+struct CliProgressState {
+    planned_files: usize,
+    planned_bytes: u64,
+    finished_files: usize,
+    finished_bytes: u64,
+    in_flight: HashMap<String, u64>,
+    last_percent: Option<u64>,
+}
+
+impl DownloadProgress for CliProgressBar {
+    fn on_event(&self, event: DownloadEvent) {
+        let mut state = self.state.lock().unwrap();
+
+        match event {
+            DownloadEvent::StageStarted(stage) => {
+                if state.last_percent.take().is_some() {
+                    println!();
+                }
+                println!("{:?}...", stage);
+                return;
+            }
+            DownloadEvent::Planned { files, bytes } => {
+                *state = CliProgressState {
+                    planned_files: files,
+                    planned_bytes: bytes,
+                    ..Default::default()
+                };
+            }
+            DownloadEvent::FileStarted { path, .. } => {
+                state.in_flight.insert(path, 0);
+            }
+            DownloadEvent::FileProgress { path, downloaded } => {
+                state.in_flight.insert(path, downloaded);
+            }
+            DownloadEvent::FileRetrying {
+                path, delay, error, ..
+            } => {
+                println!("\nRetrying {} in {:?} after {}", path, delay, error);
+            }
+            DownloadEvent::FileFinished { path, size, .. } => {
+                state.in_flight.remove(&path);
+                state.finished_files += 1;
+                state.finished_bytes += size.unwrap_or_default();
+            }
+            DownloadEvent::FileFailed { path, error, .. } => {
+                state.in_flight.remove(&path);
+                state.finished_files += 1;
+                println!("\nFailed to download {}: {}", path, error);
+            }
+            DownloadEvent::ProcessorStarted { jar, index, total } => {
+                println!("Running processor {}/{}: {}", index + 1, total, jar);
+                return;
+            }
+            DownloadEvent::Finished => {
+                if state.last_percent.take().is_some() {
+                    println!();
+                }
+                return;
+            }
+        }
+
+        let downloaded = state.finished_bytes + state.in_flight.values().sum::<u64>();
+        let percent = (downloaded * 100)
+            .checked_div(state.planned_bytes)
+            .unwrap_or(100)
+            .min(100);
+        if state.last_percent == Some(percent) && state.finished_files < state.planned_files {
+            return;
+        }
+        state.last_percent = Some(percent);
+
+        let filled = (percent / 5) as usize;
+        print!(
+            "\r[{}{}] {:>3}% ({}/{} files)",
+            "#".repeat(filled),
+            " ".repeat(20 - filled),
+            percent,
+            state.finished_files,
+            state.planned_files
+        );
+        let _ = std::io::stdout().flush();
+    }
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/// Entry of `/versions/loader/<game_version>` in the Fabric and Quilt meta APIs.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FabricLoaderVersionEntry {
+    pub loader: FabricLoaderVersion,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FabricLoaderVersion {
+    pub version: String,
+    pub maven: String,
+    pub stable: Option<bool>,
+}
+
+impl FabricLoaderVersion {
+    /// Quilt does not flag stable builds, its pre-releases carry a suffix
+    /// such as `-beta.1` instead.
+    pub fn is_stable(&self) -> bool {
+        self.stable.unwrap_or_else(|| !self.version.contains('-'))
+    }
+}
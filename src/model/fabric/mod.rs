@@ -0,0 +1,3 @@
+mod fabric_loader_version;
+
+pub use fabric_loader_version::*;
@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::mojang::MojangVersionLibrary;
+
+/// `install_profile.json` of the Forge and NeoForge installers.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgeInstallProfile {
+    pub spec: Option<u32>,
+    pub version: String,
+    pub minecraft: String,
+    pub json: Option<String>,
+    #[serde(default)]
+    pub data: HashMap<String, ForgeInstallProfileData>,
+    #[serde(default)]
+    pub processors: Vec<ForgeInstallProcessor>,
+    #[serde(default)]
+    pub libraries: Vec<MojangVersionLibrary>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgeInstallProfileData {
+    pub client: String,
+    pub server: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgeInstallProcessor {
+    pub sides: Option<Vec<String>>,
+    pub jar: String,
+    #[serde(default)]
+    pub classpath: Vec<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub outputs: HashMap<String, String>,
+}
+
+impl ForgeInstallProcessor {
+    pub fn runs_on_client(&self) -> bool {
+        self.sides
+            .as_ref()
+            .is_none_or(|sides| sides.iter().any(|side| side == "client"))
+    }
+}
@@ -0,0 +1,3 @@
+mod forge_install_profile;
+
+pub use forge_install_profile::*;
@@ -0,0 +1,70 @@
+/// A library given as Maven coordinates, `group:artifact:version[:classifier][@extension]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MavenArtifact {
+    pub group: String,
+    pub artifact: String,
+    pub version: String,
+    pub classifier: Option<String>,
+    pub extension: String,
+}
+
+impl MavenArtifact {
+    pub fn parse(name: &str) -> Option<Self> {
+        let (coordinates, extension) = match name.split_once('@') {
+            Some((coordinates, extension)) => (coordinates, extension.to_string()),
+            None => (name, "jar".to_string()),
+        };
+
+        let mut parts = coordinates.split(':');
+        let group = parts.next()?.to_string();
+        let artifact = parts.next()?.to_string();
+        let version = parts.next()?.to_string();
+        let classifier = parts.next().map(|classifier| classifier.to_string());
+
+        if parts.next().is_some() {
+            return None;
+        }
+
+        Some(Self {
+            group,
+            artifact,
+            version,
+            classifier,
+            extension,
+        })
+    }
+
+    pub fn file_name(&self) -> String {
+        match &self.classifier {
+            Some(classifier) => format!(
+                "{}-{}-{}.{}",
+                self.artifact, self.version, classifier, self.extension
+            ),
+            None => format!("{}-{}.{}", self.artifact, self.version, self.extension),
+        }
+    }
+
+    /// Relative path inside a Maven repository, always with `/` separators.
+    pub fn path(&self) -> String {
+        format!(
+            "{}/{}/{}/{}",
+            self.group.replace('.', "/"),
+            self.artifact,
+            self.version,
+            self.file_name()
+        )
+    }
+
+    pub fn build_download_url(&self, repository_url: &str) -> String {
+        format!("{}/{}", repository_url.trim_end_matches('/'), self.path())
+    }
+
+    /// Identifies the library regardless of its version, so an inheriting
+    /// profile can replace a library of its parent.
+    pub fn versionless_key(&self) -> String {
+        match &self.classifier {
+            Some(classifier) => format!("{}:{}:{}", self.group, self.artifact, classifier),
+            None => format!("{}:{}", self.group, self.artifact),
+        }
+    }
+}
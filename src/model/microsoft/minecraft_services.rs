@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MinecraftLoginRequest {
+    pub identity_token: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MinecraftLoginResponse {
+    pub access_token: String,
+    pub expires_in: u64,
+    pub token_type: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MinecraftProfile {
+    pub id: String,
+    pub name: String,
+}
+
+impl MinecraftLoginRequest {
+    pub fn new(user_hash: &str, xsts_token: &str) -> Self {
+        Self {
+            identity_token: format!("XBL3.0 x={};{}", user_hash, xsts_token),
+        }
+    }
+}
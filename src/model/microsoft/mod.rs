@@ -0,0 +1,7 @@
+mod microsoft_token;
+mod minecraft_services;
+mod xbox_live;
+
+pub use microsoft_token::*;
+pub use minecraft_services::*;
+pub use xbox_live::*;
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct XboxLiveAuthRequest {
+    pub properties: XboxLiveAuthRequestProperties,
+    pub relying_party: String,
+    pub token_type: String,
+}
+
+/// The user and XSTS requests use different properties; the endpoints reject
+/// the ones of the other request, even as `null`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct XboxLiveAuthRequestProperties {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub site_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rps_ticket: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sandbox_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_tokens: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct XboxLiveAuthResponse {
+    pub token: String,
+    pub display_claims: XboxLiveDisplayClaims,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct XboxLiveDisplayClaims {
+    pub xui: Vec<XboxLiveUserInfo>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct XboxLiveUserInfo {
+    pub uhs: String,
+    pub xid: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct XboxLiveAuthError {
+    #[serde(rename = "XErr")]
+    pub x_err: u64,
+    #[serde(rename = "Message")]
+    pub message: Option<String>,
+}
+
+impl XboxLiveAuthRequest {
+    pub fn user(microsoft_access_token: &str) -> Self {
+        Self {
+            properties: XboxLiveAuthRequestProperties {
+                auth_method: Some("RPS".to_string()),
+                site_name: Some("user.auth.xboxlive.com".to_string()),
+                rps_ticket: Some(format!("d={}", microsoft_access_token)),
+                sandbox_id: None,
+                user_tokens: None,
+            },
+            relying_party: "http://auth.xboxlive.com".to_string(),
+            token_type: "JWT".to_string(),
+        }
+    }
+
+    pub fn xsts(user_token: &str) -> Self {
+        Self {
+            properties: XboxLiveAuthRequestProperties {
+                auth_method: None,
+                site_name: None,
+                rps_ticket: None,
+                sandbox_id: Some("RETAIL".to_string()),
+                user_tokens: Some(vec![user_token.to_string()]),
+            },
+            relying_party: "rp://api.minecraftservices.com/".to_string(),
+            token_type: "JWT".to_string(),
+        }
+    }
+}
+
+impl XboxLiveAuthResponse {
+    pub fn user_hash(&self) -> Option<&XboxLiveUserInfo> {
+        self.display_claims.xui.first()
+    }
+}
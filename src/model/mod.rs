@@ -1,5 +1,11 @@
+pub mod fabric;
+pub mod forge;
+pub mod microsoft;
 pub mod mojang;
+pub mod prism;
 
+mod maven_artifact;
 mod version_type;
 
+pub use maven_artifact::MavenArtifact;
 pub use version_type::VersionType;
@@ -2,10 +2,20 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
+use crate::launcher_paths::join_paths;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct MojangAssetIndexFile {
     pub objects: HashMap<String, MojangAssetIndexFileObject>,
+    /// Set by the `legacy` index: objects are also laid out by name under
+    /// `assets/virtual/<index id>`.
+    #[serde(default)]
+    pub r#virtual: bool,
+    /// Set by the `pre-1.6` index: objects are also laid out by name under
+    /// `<game dir>/resources`.
+    #[serde(default, rename = "map_to_resources")]
+    pub map_to_resources: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -26,3 +36,9 @@ impl MojangAssetIndexFileObject {
         format!("{}/{}/{}", base_path, hash_prefix, self.hash)
     }
 }
+
+impl MojangAssetIndexFile {
+    pub fn build_named_file_path(base_path: &str, object_name: &str) -> String {
+        join_paths(base_path.to_string(), object_name.split('/').collect())
+    }
+}
@@ -0,0 +1,43 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// `launcher_profiles.json` of the official launcher. Fields this launcher
+/// does not use are kept so the file can be written back without losing
+/// them.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LauncherProfiles {
+    #[serde(default)]
+    pub profiles: BTreeMap<String, LauncherProfile>,
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LauncherProfile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// `custom`, `latest-release` or `latest-snapshot`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_version_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub game_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub java_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub java_args: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<LauncherProfileResolution>,
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct LauncherProfileResolution {
+    pub width: u32,
+    pub height: u32,
+}
@@ -1,13 +1,19 @@
 mod assets;
+mod launcher_profiles;
 mod mojang_version;
 mod mojang_version_arguments;
 mod mojang_version_manifest;
+mod mojang_version_profile;
+mod mojang_version_rule;
 mod runtime;
 mod version_library;
 
 pub use assets::*;
+pub use launcher_profiles::*;
 pub use mojang_version::*;
 pub use mojang_version_arguments::*;
 pub use mojang_version_manifest::*;
+pub use mojang_version_profile::*;
+pub use mojang_version_rule::*;
 pub use runtime::*;
 pub use version_library::*;
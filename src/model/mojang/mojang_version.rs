@@ -2,14 +2,20 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{platform::PlatformData, Result};
+use crate::{model::MavenArtifact, platform::PlatformData, Result};
 
-use super::{version_library::VersionLibrary, MojangVersionArguments};
+use super::{
+    version_library::VersionLibrary, MojangVersionArguments, MojangVersionRule, RuleContext,
+};
+
+const MOJANG_LIBRARIES_URL: &str = "https://libraries.minecraft.net/";
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct MojangVersion {
     pub id: String,
+    pub inherits_from: Option<String>,
+    pub jar: Option<String>,
     pub assets: String,
     pub asset_index: MojangVersionAssetIndex,
     pub downloads: HashMap<MojangVersionArtifactKey, MojangVersionArtifact>,
@@ -60,8 +66,11 @@ pub struct MojangVersionJavaVersion {
 #[serde(rename_all = "camelCase")]
 pub struct MojangVersionLibrary {
     pub name: String,
-    pub downloads: MojangVersionLibraryDownloads,
-    pub rules: Option<Vec<MojangVersionLibraryRule>>,
+    pub downloads: Option<MojangVersionLibraryDownloads>,
+    pub url: Option<String>,
+    pub sha1: Option<String>,
+    pub size: Option<u64>,
+    pub rules: Option<Vec<MojangVersionRule>>,
     pub natives: Option<HashMap<String, String>>,
 }
 
@@ -72,16 +81,10 @@ pub struct MojangVersionLibraryDownloads {
     pub classifiers: Option<HashMap<String, MojangVersionLibraryClassifier>>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct MojangVersionLibraryRule {
-    pub action: String,
-    pub os: Option<HashMap<String, String>>,
-}
-
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct MojangVersionLibraryArtifact {
+    pub path: Option<String>,
     pub sha1: String,
     pub size: u64,
     pub url: String,
@@ -114,6 +117,19 @@ pub struct MojangVersionLoggingFile {
 }
 
 impl MojangVersion {
+    pub fn client_logging(&self) -> Option<&MojangVersionLogging> {
+        self.logging.as_ref()?.get("client")
+    }
+
+    /// Id of the version whose client jar is used. Inheriting versions run
+    /// on the jar of the version they extend.
+    pub fn client_jar_id(&self) -> &str {
+        self.jar
+            .as_deref()
+            .or(self.inherits_from.as_deref())
+            .unwrap_or(&self.id)
+    }
+
     pub fn filter_libraries_by_platform_data(
         &self,
         platform_data: &PlatformData,
@@ -121,11 +137,8 @@ impl MojangVersion {
         self.libraries
             .iter()
             .filter(|library| {
-                if let Some(rules) = library.rules.clone() {
-                    rules.iter().any(|rule| rule.is_allowed(platform_data))
-                } else {
-                    true
-                }
+                let rules = library.rules.as_deref().unwrap_or_default();
+                MojangVersionRule::is_allowed(rules, &RuleContext::new(platform_data))
             })
             .map(|library| library.into_version_library(platform_data))
             .collect()
@@ -145,57 +158,64 @@ impl MojangVersionLibrary {
         let native_classifier_name = match self.natives.clone() {
             Some(natives) => natives
                 .get(&native_id)
-                .map(|classifier| classifier.replace("${arch}", &arch_as_string)),
+                .map(|classifier| classifier.replace("${arch}", arch_as_string)),
             None => None,
         };
 
+        let downloads = self.downloads.as_ref();
+
         match native_classifier_name {
             Some(native_classifier_name) => {
-                if let Some(classifiers) = self.downloads.classifiers.clone() {
-                    let classifier = classifiers
-                        .get(&native_classifier_name)
-                        .ok_or(crate::Error::LibraryDownloadNotFound(self.name.clone()))?;
-
-                    Ok(VersionLibrary::new(
-                        classifier.sha1.clone(),
-                        classifier.size,
-                        classifier.url.clone(),
-                        true,
-                    ))
-                } else {
-                    Err(crate::Error::LibraryDownloadNotFound(self.name.clone()))
-                }
-            }
-            None => {
-                let artifact = self
-                    .downloads
-                    .artifact
-                    .clone()
+                let classifier = downloads
+                    .and_then(|downloads| downloads.classifiers.as_ref())
+                    .and_then(|classifiers| classifiers.get(&native_classifier_name))
                     .ok_or(crate::Error::LibraryDownloadNotFound(self.name.clone()))?;
 
                 Ok(VersionLibrary::new(
-                    artifact.sha1.clone(),
-                    artifact.size,
-                    artifact.url.clone(),
-                    false,
+                    classifier.path.clone(),
+                    Some(classifier.sha1.clone()),
+                    Some(classifier.size),
+                    classifier.url.clone(),
+                    true,
                 ))
             }
-        }
-    }
-}
+            None => match downloads.and_then(|downloads| downloads.artifact.as_ref()) {
+                Some(artifact) => {
+                    let path = match &artifact.path {
+                        Some(path) => path.clone(),
+                        None => self.maven_artifact()?.path(),
+                    };
 
-impl MojangVersionLibraryRule {
-    fn is_allowed(&self, platform_data: &PlatformData) -> bool {
-        if self.action == "allow" {
-            if let Some(os) = &self.os {
-                if let Some(allowed_os) = os.get("name") {
-                    return allowed_os == &platform_data.platform_type.native_id();
+                    Ok(VersionLibrary::new(
+                        path,
+                        Some(artifact.sha1.clone()),
+                        Some(artifact.size),
+                        artifact.url.clone(),
+                        false,
+                    ))
                 }
-            } else {
-                return true;
-            }
+                None => self.to_maven_version_library(),
+            },
         }
+    }
+
+    /// Libraries that only carry Maven coordinates and, optionally, the
+    /// repository they live in.
+    fn to_maven_version_library(&self) -> Result<VersionLibrary> {
+        let maven_artifact = self.maven_artifact()?;
+        let repository_url = self.url.clone().unwrap_or(MOJANG_LIBRARIES_URL.to_string());
+
+        Ok(VersionLibrary::new(
+            maven_artifact.path(),
+            self.sha1.clone(),
+            self.size,
+            maven_artifact.build_download_url(&repository_url),
+            false,
+        ))
+    }
 
-        false
+    pub fn maven_artifact(&self) -> Result<MavenArtifact> {
+        MavenArtifact::parse(&self.name)
+            .ok_or(crate::Error::LibraryDownloadNotFound(self.name.clone()))
     }
 }
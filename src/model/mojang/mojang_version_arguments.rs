@@ -1,11 +1,13 @@
 use serde::{Deserialize, Serialize};
 
-use crate::platform::PlatformData;
+use super::{MojangVersionRule, RuleContext};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct MojangVersionArguments {
+    #[serde(default)]
     pub game: Vec<MojangVersionArgumentEntry>,
+    #[serde(default)]
     pub jvm: Vec<MojangVersionArgumentEntry>,
 }
 
@@ -20,14 +22,7 @@ pub enum MojangVersionArgumentEntry {
 #[serde(rename_all = "camelCase")]
 pub struct MojangVersionArgumentRuleBasedValue {
     pub value: Option<MojangVersionArgumentRuleValue>,
-    pub rules: Vec<MojangVersionArgumentRule>,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct MojangVersionArgumentRule {
-    pub action: String,
-    pub os: Option<MojangVersionArgumentRuleOs>,
+    pub rules: Vec<MojangVersionRule>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -37,52 +32,42 @@ pub enum MojangVersionArgumentRuleValue {
     Array(Vec<String>),
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct MojangVersionArgumentRuleOs {
-    pub name: Option<String>,
-    pub arch: Option<String>,
-}
-
 impl MojangVersionArguments {
-    pub fn select_arguments(&self, platform_data: &PlatformData) -> (Vec<String>, Vec<String>) {
-        let mut game_args = Vec::new();
-        let mut jvm_args = Vec::new();
+    /// Returns the game and JVM arguments allowed in the given context.
+    pub fn select_arguments(&self, context: &RuleContext) -> (Vec<String>, Vec<String>) {
+        (
+            Self::select_entries(&self.game, context),
+            Self::select_entries(&self.jvm, context),
+        )
+    }
 
-        for arg in &self.game {
-            match arg {
-                MojangVersionArgumentEntry::String(value) => game_args.push(value.clone()),
-                _ => {}
-            }
-        }
+    fn select_entries(
+        entries: &[MojangVersionArgumentEntry],
+        context: &RuleContext,
+    ) -> Vec<String> {
+        let mut arguments = Vec::new();
 
-        for arg in &self.jvm {
-            match arg {
-                MojangVersionArgumentEntry::String(value) => jvm_args.push(value.clone()),
+        for entry in entries {
+            match entry {
+                MojangVersionArgumentEntry::String(value) => arguments.push(value.clone()),
                 MojangVersionArgumentEntry::RuleBasedArgument(rule_based_value) => {
-                    for rule in &rule_based_value.rules {
-                        if let Some(os) = &rule.os {
-                            if let Some(name) = &os.name {
-                                if name != &platform_data.platform_type.native_id() {
-                                    continue;
-                                }
-                            }
-                        }
+                    if !MojangVersionRule::is_allowed(&rule_based_value.rules, context) {
+                        continue;
+                    }
 
-                        match &rule_based_value.value {
-                            Some(MojangVersionArgumentRuleValue::String(value)) => {
-                                jvm_args.push(value.clone());
-                            }
-                            Some(MojangVersionArgumentRuleValue::Array(values)) => {
-                                jvm_args.extend(values.clone());
-                            }
-                            None => {}
+                    match &rule_based_value.value {
+                        Some(MojangVersionArgumentRuleValue::String(value)) => {
+                            arguments.push(value.clone());
+                        }
+                        Some(MojangVersionArgumentRuleValue::Array(values)) => {
+                            arguments.extend(values.clone());
                         }
+                        None => {}
                     }
                 }
             }
         }
 
-        (game_args, jvm_args)
+        arguments
     }
 }
@@ -22,6 +22,8 @@ pub struct MojangVersionManifestVersion {
     pub id: String,
     pub r#type: VersionType,
     pub url: String,
+    /// Hash of the version JSON, only listed by the v2 manifest.
+    pub sha1: Option<String>,
     pub time: String,
     pub release_time: String,
 }
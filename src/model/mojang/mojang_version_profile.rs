@@ -0,0 +1,205 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::VersionType;
+
+use super::{
+    MojangVersion, MojangVersionArguments, MojangVersionArtifact, MojangVersionArtifactKey,
+    MojangVersionAssetIndex, MojangVersionJavaVersion, MojangVersionLibrary, MojangVersionLogging,
+    MojangVersionManifestVersion,
+};
+
+/// A version JSON that may extend another one through `inheritsFrom`, like
+/// the profiles written by mod loader installers. Every field is optional
+/// since anything missing is taken from the parent.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MojangVersionProfile {
+    pub id: String,
+    pub inherits_from: Option<String>,
+    pub r#type: Option<VersionType>,
+    pub time: Option<String>,
+    pub release_time: Option<String>,
+    pub jar: Option<String>,
+    pub assets: Option<String>,
+    pub asset_index: Option<MojangVersionAssetIndex>,
+    pub downloads: Option<HashMap<MojangVersionArtifactKey, MojangVersionArtifact>>,
+    pub java_version: Option<MojangVersionJavaVersion>,
+    pub main_class: Option<String>,
+    pub minecraft_arguments: Option<String>,
+    pub arguments: Option<MojangVersionArguments>,
+    #[serde(default)]
+    pub libraries: Vec<MojangVersionLibrary>,
+    pub logging: Option<HashMap<String, MojangVersionLogging>>,
+}
+
+impl MojangVersionProfile {
+    /// Describes an installed version that is not part of the Mojang
+    /// manifest. It has no download url since its JSON is already on disk.
+    pub fn to_manifest_version(&self) -> MojangVersionManifestVersion {
+        MojangVersionManifestVersion {
+            id: self.id.clone(),
+            r#type: self.r#type.clone().unwrap_or(VersionType::Release),
+            url: String::new(),
+            sha1: None,
+            time: self.time.clone().unwrap_or_default(),
+            release_time: self.release_time.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Merges this profile on top of its parent. Own libraries come first and
+    /// replace parent libraries with the same group and artifact, arguments
+    /// are appended to the parent ones and every other field overrides.
+    pub fn resolve(self, parent: MojangVersion) -> MojangVersion {
+        let overridden_libraries: HashSet<String> = self
+            .libraries
+            .iter()
+            .filter_map(|library| library.maven_artifact().ok())
+            .map(|artifact| artifact.versionless_key())
+            .collect();
+
+        let mut libraries = self.libraries;
+        libraries.extend(parent.libraries.into_iter().filter(|library| {
+            library
+                .maven_artifact()
+                .map(|artifact| !overridden_libraries.contains(&artifact.versionless_key()))
+                .unwrap_or(true)
+        }));
+
+        let arguments = match (parent.arguments, self.arguments) {
+            (Some(mut parent_arguments), Some(arguments)) => {
+                parent_arguments.game.extend(arguments.game);
+                parent_arguments.jvm.extend(arguments.jvm);
+                Some(parent_arguments)
+            }
+            (parent_arguments, arguments) => arguments.or(parent_arguments),
+        };
+
+        MojangVersion {
+            id: self.id,
+            jar: self.jar.or(parent.jar).or(Some(parent.id.clone())),
+            inherits_from: Some(parent.id),
+            assets: self.assets.unwrap_or(parent.assets),
+            asset_index: self.asset_index.unwrap_or(parent.asset_index),
+            downloads: self.downloads.unwrap_or(parent.downloads),
+            java_version: self.java_version.unwrap_or(parent.java_version),
+            main_class: self.main_class.unwrap_or(parent.main_class),
+            minecraft_arguments: self.minecraft_arguments.or(parent.minecraft_arguments),
+            arguments,
+            libraries,
+            logging: self.logging.or(parent.logging),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    struct Case {
+        name: &'static str,
+        profile: serde_json::Value,
+        libraries: Vec<&'static str>,
+        game_arguments: serde_json::Value,
+        main_class: &'static str,
+        jar: &'static str,
+    }
+
+    fn parent_version() -> MojangVersion {
+        serde_json::from_value(json!({
+            "id": "1.20.1",
+            "assets": "5",
+            "assetIndex": { "id": "5", "sha1": "a", "size": 1, "totalSize": 1, "url": "" },
+            "downloads": {},
+            "javaVersion": { "component": "java-runtime-gamma", "majorVersion": 17 },
+            "mainClass": "net.minecraft.client.main.Main",
+            "arguments": { "game": ["--username", "${auth_player_name}"], "jvm": [] },
+            "libraries": [
+                { "name": "org.ow2.asm:asm:9.3" },
+                { "name": "org.lwjgl:lwjgl:3.3.1" }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn resolves_onto_parent() {
+        let cases = vec![
+            Case {
+                name: "library override by versionless key",
+                profile: json!({
+                    "id": "1.20.1-forge-47.2.0",
+                    "libraries": [
+                        { "name": "org.ow2.asm:asm:9.5" },
+                        { "name": "net.minecraftforge:fmlloader:1.20.1-47.2.0" }
+                    ]
+                }),
+                libraries: vec![
+                    "org.ow2.asm:asm:9.5",
+                    "net.minecraftforge:fmlloader:1.20.1-47.2.0",
+                    "org.lwjgl:lwjgl:3.3.1",
+                ],
+                game_arguments: json!(["--username", "${auth_player_name}"]),
+                main_class: "net.minecraft.client.main.Main",
+                jar: "1.20.1",
+            },
+            Case {
+                name: "arguments appended",
+                profile: json!({
+                    "id": "1.20.1-forge-47.2.0",
+                    "arguments": { "game": ["--launchTarget", "forgeclient"] }
+                }),
+                libraries: vec!["org.ow2.asm:asm:9.3", "org.lwjgl:lwjgl:3.3.1"],
+                game_arguments: json!([
+                    "--username",
+                    "${auth_player_name}",
+                    "--launchTarget",
+                    "forgeclient"
+                ]),
+                main_class: "net.minecraft.client.main.Main",
+                jar: "1.20.1",
+            },
+            Case {
+                name: "own main class and jar",
+                profile: json!({
+                    "id": "1.20.1-fabric",
+                    "mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient",
+                    "jar": "1.20.1-patched"
+                }),
+                libraries: vec!["org.ow2.asm:asm:9.3", "org.lwjgl:lwjgl:3.3.1"],
+                game_arguments: json!(["--username", "${auth_player_name}"]),
+                main_class: "net.fabricmc.loader.impl.launch.knot.KnotClient",
+                jar: "1.20.1-patched",
+            },
+        ];
+
+        for case in cases {
+            let profile: MojangVersionProfile = serde_json::from_value(case.profile).unwrap();
+            let version = profile.resolve(parent_version());
+
+            let libraries: Vec<&str> = version
+                .libraries
+                .iter()
+                .map(|library| library.name.as_str())
+                .collect();
+            assert_eq!(libraries, case.libraries, "{}", case.name);
+            assert_eq!(
+                serde_json::to_value(&version.arguments.unwrap().game).unwrap(),
+                case.game_arguments,
+                "{}",
+                case.name
+            );
+            assert_eq!(version.main_class, case.main_class, "{}", case.name);
+            assert_eq!(version.jar.as_deref(), Some(case.jar), "{}", case.name);
+            assert_eq!(
+                version.inherits_from.as_deref(),
+                Some("1.20.1"),
+                "{}",
+                case.name
+            );
+        }
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::platform::PlatformData;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MojangVersionRuleAction {
+    Allow,
+    Disallow,
+}
+
+/// Rule attached to libraries and arguments of a version JSON.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MojangVersionRule {
+    pub action: MojangVersionRuleAction,
+    pub os: Option<MojangVersionRuleOs>,
+    pub features: Option<HashMap<String, bool>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MojangVersionRuleOs {
+    pub name: Option<String>,
+    pub arch: Option<String>,
+    pub version: Option<String>,
+}
+
+/// What rules are evaluated against: the current platform and the launcher
+/// features that are enabled.
+#[derive(Debug, Clone)]
+pub struct RuleContext<'a> {
+    pub platform_data: &'a PlatformData,
+    pub features: HashMap<String, bool>,
+}
+
+impl<'a> RuleContext<'a> {
+    pub fn new(platform_data: &'a PlatformData) -> Self {
+        Self {
+            platform_data,
+            features: HashMap::new(),
+        }
+    }
+
+    pub fn with_feature(mut self, feature: &str, enabled: bool) -> Self {
+        self.features.insert(feature.to_string(), enabled);
+        self
+    }
+
+    fn is_feature_enabled(&self, feature: &str) -> bool {
+        self.features.get(feature).copied().unwrap_or(false)
+    }
+}
+
+impl MojangVersionRule {
+    /// Evaluates rules in order: nothing is allowed unless a rule matches, and
+    /// the last matching rule decides. An empty list allows everything.
+    pub fn is_allowed(rules: &[MojangVersionRule], context: &RuleContext) -> bool {
+        if rules.is_empty() {
+            return true;
+        }
+
+        rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(context))
+            .is_some_and(|rule| rule.action == MojangVersionRuleAction::Allow)
+    }
+
+    fn matches(&self, context: &RuleContext) -> bool {
+        let os_matches = self.os.as_ref().is_none_or(|os| os.matches(context));
+        let features_match = self.features.as_ref().is_none_or(|features| {
+            features
+                .iter()
+                .all(|(feature, value)| context.is_feature_enabled(feature) == *value)
+        });
+
+        os_matches && features_match
+    }
+}
+
+impl MojangVersionRuleOs {
+    fn matches(&self, context: &RuleContext) -> bool {
+        let platform_data = context.platform_data;
+
+        let name_matches = self
+            .name
+            .as_ref()
+            .is_none_or(|name| name == &platform_data.platform_type.native_id());
+        let arch_matches = self
+            .arch
+            .as_ref()
+            .is_none_or(|arch| arch == platform_data.arch.rule_id());
+        let version_matches = self.version.as_ref().is_none_or(|version| {
+            Regex::new(version)
+                .map(|regex| regex.is_match(&platform_data.os_version))
+                .unwrap_or(false)
+        });
+
+        name_matches && arch_matches && version_matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::platform::{Arch, PlatformType};
+
+    fn platform(platform_type: PlatformType, arch: Arch, os_version: &str) -> PlatformData {
+        PlatformData {
+            platform_type,
+            arch,
+            os_version: os_version.to_string(),
+        }
+    }
+
+    fn rules(value: serde_json::Value) -> Vec<MojangVersionRule> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn evaluates_os_rules() {
+        let windows_10 = platform(PlatformType::Windows, Arch::X86_64, "10.0.19045");
+        let linux_arm = platform(PlatformType::Linux, Arch::Aarch64, "6.1.0");
+        let osx = platform(PlatformType::MacOs, Arch::X86_64, "10.5.8");
+
+        let cases = [
+            ("empty list allows", json!([]), &linux_arm, true),
+            (
+                "allow without os",
+                json!([{ "action": "allow" }]),
+                &linux_arm,
+                true,
+            ),
+            (
+                "disallow without os",
+                json!([{ "action": "disallow" }]),
+                &linux_arm,
+                false,
+            ),
+            (
+                "no matching rule disallows",
+                json!([{ "action": "allow", "os": { "name": "windows" } }]),
+                &linux_arm,
+                false,
+            ),
+            (
+                "os name matches",
+                json!([{ "action": "allow", "os": { "name": "osx" } }]),
+                &osx,
+                true,
+            ),
+            (
+                "last match wins",
+                json!([
+                    { "action": "allow" },
+                    { "action": "disallow", "os": { "name": "osx" } }
+                ]),
+                &osx,
+                false,
+            ),
+            (
+                "last match wins on other os",
+                json!([
+                    { "action": "allow" },
+                    { "action": "disallow", "os": { "name": "osx" } }
+                ]),
+                &windows_10,
+                true,
+            ),
+            (
+                "arch uses rule ids",
+                json!([{ "action": "allow", "os": { "arch": "arm64" } }]),
+                &linux_arm,
+                true,
+            ),
+            (
+                "arch mismatch",
+                json!([{ "action": "allow", "os": { "arch": "x86" } }]),
+                &windows_10,
+                false,
+            ),
+            (
+                "version regex matches",
+                json!([{ "action": "allow", "os": { "name": "windows", "version": "^10\\." } }]),
+                &windows_10,
+                true,
+            ),
+            (
+                "version regex disallows",
+                json!([{ "action": "disallow", "os": { "name": "osx", "version": "^10\\.5\\.\\d$" } }]),
+                &osx,
+                false,
+            ),
+            (
+                "invalid version regex never matches",
+                json!([{ "action": "allow", "os": { "version": "(" } }]),
+                &windows_10,
+                false,
+            ),
+        ];
+
+        for (name, value, platform_data, expected) in cases {
+            let context = RuleContext::new(platform_data);
+            assert_eq!(
+                MojangVersionRule::is_allowed(&rules(value), &context),
+                expected,
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn evaluates_feature_rules() {
+        let platform_data = platform(PlatformType::Linux, Arch::X86_64, "6.1.0");
+        let demo_rule = json!([{ "action": "allow", "features": { "is_demo_user": true } }]);
+
+        let cases = [
+            (
+                "missing feature is disabled",
+                demo_rule.clone(),
+                vec![],
+                false,
+            ),
+            (
+                "enabled feature",
+                demo_rule.clone(),
+                vec![("is_demo_user", true)],
+                true,
+            ),
+            (
+                "disabled feature",
+                demo_rule,
+                vec![("is_demo_user", false)],
+                false,
+            ),
+            (
+                "feature required to be false",
+                json!([{ "action": "allow", "features": { "has_custom_resolution": false } }]),
+                vec![],
+                true,
+            ),
+            (
+                "every feature must match",
+                json!([{
+                    "action": "allow",
+                    "features": { "has_quick_plays_support": true, "is_quick_play_singleplayer": true }
+                }]),
+                vec![("has_quick_plays_support", true)],
+                false,
+            ),
+        ];
+
+        for (name, value, features, expected) in cases {
+            let context = features.into_iter().fold(
+                RuleContext::new(&platform_data),
+                |context, (feature, enabled)| context.with_feature(feature, enabled),
+            );
+            assert_eq!(
+                MojangVersionRule::is_allowed(&rules(value), &context),
+                expected,
+                "{}",
+                name
+            );
+        }
+    }
+}
@@ -72,7 +72,7 @@ impl RuntimeIndex {
             },
         };
 
-        let first_runtime_entry = entry.get(version_runtime_name.as_str())?.get(0).cloned()?;
+        let first_runtime_entry = entry.get(version_runtime_name.as_str())?.first().cloned()?;
 
         Some(first_runtime_entry.manifest)
     }
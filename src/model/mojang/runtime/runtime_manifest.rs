@@ -2,7 +2,11 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{files::load_file_or_download, launcher_paths::join_paths, Result};
+use crate::{
+    download_plan::{DownloadKind, DownloadPlan, DownloadTask},
+    launcher_paths::join_paths,
+    Result,
+};
 
 type FileName = String;
 
@@ -15,6 +19,9 @@ pub struct RuntimeManifest {
 pub struct RuntimeManifestFile {
     pub r#type: FileType,
     pub downloads: Option<RuntimeManifestFileDownloads>,
+    /// Set on `bin/java` and the other programs of the runtime.
+    #[serde(default)]
+    pub executable: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
@@ -39,15 +46,28 @@ pub struct RuntimeManifestFileDownloadEntry {
 }
 
 impl RuntimeManifest {
-    pub async fn download(&self, base_path: String) -> Result<()> {
+    pub fn plan_downloads(&self, plan: &mut DownloadPlan, base_path: String) {
         for (file_name, file) in self.files.iter() {
-            match file.r#type {
-                FileType::File => {
-                    let download_entry = file.downloads.as_ref().unwrap().raw.clone();
-                    let file_path = self.join_runtime_paths(base_path.clone(), file_name.clone());
-                    load_file_or_download(file_path, download_entry.url).await?;
-                }
-                _ => {}
+            if file.r#type == FileType::File {
+                let download_entry = file.downloads.as_ref().unwrap().raw.clone();
+                plan.add(DownloadTask {
+                    kind: DownloadKind::Runtime,
+                    path: self.join_runtime_paths(base_path.clone(), file_name.clone()),
+                    url: download_entry.url,
+                    sha1: Some(download_entry.sha1),
+                    size: Some(download_entry.size.into()),
+                });
+            }
+        }
+    }
+
+    /// Downloads are written without the executable bit, so it is set on
+    /// the files the manifest marks as executable once they are on disk.
+    pub async fn mark_executables(&self, base_path: String) -> Result<()> {
+        for (file_name, file) in self.files.iter() {
+            if file.r#type == FileType::File && file.executable {
+                let path = self.join_runtime_paths(base_path.clone(), file_name.clone());
+                set_executable(&path).await?;
             }
         }
 
@@ -64,3 +84,60 @@ impl RuntimeManifest {
         }
     }
 }
+
+#[cfg(unix)]
+async fn set_executable(path: &str) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut permissions = tokio::fs::metadata(path).await?.permissions();
+    permissions.set_mode(permissions.mode() | 0o755);
+    tokio::fs::set_permissions(path, permissions).await?;
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn set_executable(_path: &str) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn marks_executable_files() {
+        let base_path =
+            std::env::temp_dir().join(format!("minecraft-launcher-runtime-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&base_path);
+        std::fs::create_dir_all(base_path.join("bin")).unwrap();
+        std::fs::write(base_path.join("bin").join("java"), "").unwrap();
+        std::fs::write(base_path.join("release"), "").unwrap();
+
+        let download = serde_json::json!({ "raw": { "sha1": "", "size": 0, "url": "" } });
+        let manifest: RuntimeManifest = serde_json::from_value(serde_json::json!({
+            "files": {
+                "bin": { "type": "directory" },
+                "bin/java": { "type": "file", "executable": true, "downloads": download },
+                "release": { "type": "file", "executable": false, "downloads": download }
+            }
+        }))
+        .unwrap();
+
+        manifest
+            .mark_executables(base_path.to_string_lossy().to_string())
+            .await
+            .unwrap();
+
+        let mode = |name: &str| {
+            std::fs::metadata(base_path.join(name))
+                .unwrap()
+                .permissions()
+                .mode()
+        };
+        assert_eq!(mode("bin/java") & 0o111, 0o111);
+        assert_eq!(mode("release") & 0o111, 0);
+    }
+}
@@ -2,15 +2,23 @@ use crate::launcher_paths::join_paths;
 
 #[derive(Debug, Clone)]
 pub struct VersionLibrary {
-    pub sha1: String,
-    pub size: u64,
+    pub path: String,
+    pub sha1: Option<String>,
+    pub size: Option<u64>,
     pub url: String,
     pub is_native: bool,
 }
 
 impl VersionLibrary {
-    pub fn new(sha1: String, size: u64, url: String, is_native: bool) -> Self {
+    pub fn new(
+        path: String,
+        sha1: Option<String>,
+        size: Option<u64>,
+        url: String,
+        is_native: bool,
+    ) -> Self {
         Self {
+            path,
             sha1,
             size,
             url,
@@ -23,12 +31,8 @@ impl VersionLibrary {
     }
 
     pub fn get_path(&self) -> String {
-        Self::extract_path_from_url(&self.url)
-    }
-
-    fn extract_path_from_url(url: &str) -> String {
-        let mut path_parts: Vec<&str> = url.split('/').skip(3).collect();
+        let mut path_parts: Vec<&str> = self.path.split('/').collect();
 
-        join_paths(path_parts.remove(0).to_owned(), path_parts).to_string()
+        join_paths(path_parts.remove(0).to_owned(), path_parts)
     }
 }
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+/// `mmc-pack.json` of a Prism Launcher or MultiMC instance, listing the
+/// components the instance is built from.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MmcPack {
+    pub format_version: Option<u32>,
+    pub components: Vec<MmcPackComponent>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MmcPackComponent {
+    /// Such as `net.minecraft` or `net.fabricmc.fabric-loader`.
+    pub uid: String,
+    pub version: Option<String>,
+    /// Resolved version, written when `version` was left to a dependency.
+    pub cached_version: Option<String>,
+}
+
+impl MmcPackComponent {
+    pub fn resolved_version(&self) -> Option<&str> {
+        self.version.as_deref().or(self.cached_version.as_deref())
+    }
+}
@@ -0,0 +1,3 @@
+mod mmc_pack;
+
+pub use mmc_pack::*;
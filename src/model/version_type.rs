@@ -11,3 +11,14 @@ pub enum VersionType {
     #[serde(rename = "old_beta")]
     OldBeta,
 }
+
+impl VersionType {
+    pub fn id(&self) -> &'static str {
+        match self {
+            VersionType::Release => "release",
+            VersionType::Snapshot => "snapshot",
+            VersionType::OldAlpha => "old_alpha",
+            VersionType::OldBeta => "old_beta",
+        }
+    }
+}
@@ -4,6 +4,7 @@ use crate::Result;
 pub struct PlatformData {
     pub platform_type: PlatformType,
     pub arch: Arch,
+    pub os_version: String,
 }
 
 #[derive(Debug)]
@@ -43,9 +44,12 @@ impl PlatformData {
             }
         };
 
+        let os_version = os_info::get().version().to_string();
+
         Ok(Self {
             platform_type,
             arch,
+            os_version,
         })
     }
 }
@@ -58,4 +62,23 @@ impl PlatformType {
         }
         .to_string()
     }
+
+    pub fn classpath_separator(&self) -> &'static str {
+        match self {
+            PlatformType::Windows => ";",
+            _ => ":",
+        }
+    }
+}
+
+impl Arch {
+    /// Value compared with `os.arch` in version rules.
+    pub fn rule_id(&self) -> &'static str {
+        match self {
+            Arch::X86 => "x86",
+            Arch::X86_64 => "x86_64",
+            Arch::Arm => "arm",
+            Arch::Aarch64 => "arm64",
+        }
+    }
 }
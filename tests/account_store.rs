@@ -0,0 +1,124 @@
+mod common;
+
+use common::{start_auth_server, temp_dir};
+use minecraft_launcher::{
+    accounts::AccountStore,
+    auth::{AuthEndpoints, AuthSession, MicrosoftAuthenticator},
+    LauncherPath, LauncherPaths,
+};
+
+fn microsoft_session(expires_at: u64) -> AuthSession {
+    AuthSession {
+        username: "Notch".to_string(),
+        uuid: "069a79f444e94726a5befca90e38aaf5".to_string(),
+        access_token: "stored-access-token".to_string(),
+        xuid: Some("2535".to_string()),
+        user_type: "msa".to_string(),
+        expires_at,
+        refresh_token: Some("stored-refresh-token".to_string()),
+    }
+}
+
+fn launcher_paths(name: &str) -> LauncherPaths {
+    LauncherPaths::new(temp_dir(name).to_string_lossy().to_string())
+}
+
+#[tokio::test]
+async fn persists_and_reloads_accounts() {
+    let launcher_paths = launcher_paths("accounts-reload");
+    let mut account_store = AccountStore::load(launcher_paths.clone()).await.unwrap();
+    account_store.add_offline_account("Steve".to_string());
+    let account = account_store.add_microsoft_account(&microsoft_session(u64::MAX));
+    account_store.select_account(&account.id).unwrap();
+    account_store.persist().await.unwrap();
+
+    let mut account_store = AccountStore::load(launcher_paths.clone()).await.unwrap();
+
+    assert_eq!(account_store.accounts().len(), 2);
+    assert_eq!(account_store.selected_account().unwrap().id, account.id);
+    let session = account_store.selected_session(None).await.unwrap().unwrap();
+    assert_eq!(session.access_token, "stored-access-token");
+
+    let accounts_json =
+        std::fs::read_to_string(launcher_paths.get_path(LauncherPath::AccountsFile)).unwrap();
+    assert!(!accounts_json.contains("stored-access-token"));
+    assert!(!accounts_json.contains("stored-refresh-token"));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn keeps_secrets_private() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let launcher_paths = launcher_paths("accounts-secrets");
+    let mut account_store = AccountStore::load(launcher_paths.clone()).await.unwrap();
+    account_store.add_microsoft_account(&microsoft_session(u64::MAX));
+    account_store.persist().await.unwrap();
+
+    let metadata =
+        std::fs::metadata(launcher_paths.get_path(LauncherPath::AccountSecretsFile)).unwrap();
+    assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+}
+
+#[tokio::test]
+async fn selects_and_removes_accounts() {
+    let launcher_paths = launcher_paths("accounts-select");
+    let mut account_store = AccountStore::load(launcher_paths).await.unwrap();
+    let steve = account_store.add_offline_account("Steve".to_string());
+    let alex = account_store.add_offline_account("Alex".to_string());
+
+    assert!(account_store.selected_account().is_none());
+    assert!(matches!(
+        account_store.select_account("missing"),
+        Err(minecraft_launcher::Error::AccountNotFound(_))
+    ));
+
+    account_store.select_account(&alex.id).unwrap();
+    assert_eq!(account_store.selected_account().unwrap().username, "Alex");
+
+    account_store.remove_account(&alex.id).unwrap();
+    assert!(account_store.selected_account().is_none());
+    assert_eq!(account_store.accounts().len(), 1);
+    assert_eq!(account_store.accounts()[0].id, steve.id);
+
+    // Signing in again with the same profile updates the stored account.
+    let first = account_store.add_microsoft_account(&microsoft_session(u64::MAX));
+    let second = account_store.add_microsoft_account(&microsoft_session(u64::MAX));
+    assert_eq!(first.id, second.id);
+    assert_eq!(account_store.accounts().len(), 2);
+}
+
+#[tokio::test]
+async fn refreshes_expired_sessions() {
+    let server = start_auth_server().await;
+    let authenticator = MicrosoftAuthenticator::new(
+        "client-id".to_string(),
+        AuthEndpoints::with_base_url(&server.base_url),
+    );
+    let launcher_paths = launcher_paths("accounts-refresh");
+    let mut account_store = AccountStore::load(launcher_paths.clone()).await.unwrap();
+    let account = account_store.add_microsoft_account(&microsoft_session(0));
+    account_store.select_account(&account.id).unwrap();
+
+    assert!(matches!(
+        account_store.selected_session(None).await,
+        Err(minecraft_launcher::Error::AccountSessionExpired(_))
+    ));
+
+    let session = account_store
+        .selected_session(Some(&authenticator))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(session.access_token, "minecraft-access-token");
+    assert_eq!(
+        session.refresh_token.as_deref(),
+        Some("microsoft-refresh-token")
+    );
+
+    // The refreshed tokens were persisted.
+    let mut account_store = AccountStore::load(launcher_paths).await.unwrap();
+    let session = account_store.selected_session(None).await.unwrap().unwrap();
+    assert_eq!(session.access_token, "minecraft-access-token");
+    assert_eq!(account_store.accounts().len(), 1);
+}
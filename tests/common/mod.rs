@@ -0,0 +1,229 @@
+//! A minimal HTTP/1.1 server to run the launcher against local endpoints.
+#![allow(dead_code)]
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use serde_json::json;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    pub method: String,
+    pub path: String,
+    /// Header names are lowercased.
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+type Handler = dyn Fn(&MockRequest) -> MockResponse + Send + Sync;
+
+pub struct MockServer {
+    pub base_url: String,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+}
+
+impl MockRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+
+    pub fn json(&self) -> serde_json::Value {
+        serde_json::from_slice(&self.body).unwrap()
+    }
+}
+
+impl MockResponse {
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    pub fn json(status: u16, value: serde_json::Value) -> Self {
+        Self::new(status, value.to_string()).with_header("Content-Type", "application/json")
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+impl MockServer {
+    /// Serves every request with `handler` until the test ends.
+    pub async fn start(
+        handler: impl Fn(&MockRequest) -> MockResponse + Send + Sync + 'static,
+    ) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler: Arc<Handler> = Arc::new(handler);
+
+        let server_requests = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let handler = handler.clone();
+                let requests = server_requests.clone();
+                tokio::spawn(handle_connection(stream, handler, requests));
+            }
+        });
+
+        Self { base_url, requests }
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+/// Requests are recorded before they are answered, so the client never sees
+/// a response whose request is missing from `requests`.
+async fn handle_connection(
+    mut stream: TcpStream,
+    handler: Arc<Handler>,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+) -> Option<()> {
+    let mut buffer = Vec::new();
+    let header_end = loop {
+        let mut chunk = [0; 4096];
+        let read = stream.read(&mut chunk).await.ok()?;
+        if read == 0 {
+            return None;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+
+        if let Some(position) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            break position + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect::<HashMap<_, _>>();
+
+    let content_length = headers
+        .get("content-length")
+        .and_then(|value| value.parse::<usize>().ok())
+        .unwrap_or_default();
+    let mut body = buffer[header_end..].to_vec();
+    while body.len() < content_length {
+        let mut chunk = [0; 4096];
+        let read = stream.read(&mut chunk).await.ok()?;
+        if read == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+
+    let request = MockRequest {
+        method,
+        path,
+        headers,
+        body,
+    };
+    let response = handler(&request);
+    requests.lock().unwrap().push(request);
+
+    let mut head = format!(
+        "HTTP/1.1 {} Mock\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        response.body.len()
+    );
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+
+    stream.write_all(head.as_bytes()).await.ok()?;
+    stream.write_all(&response.body).await.ok()?;
+    stream.shutdown().await.ok()
+}
+
+/// Answers every request of the Microsoft, Xbox and Minecraft sign-in for
+/// the player `Notch`.
+pub async fn start_auth_server() -> MockServer {
+    MockServer::start(|request| match request.path.as_str() {
+        "/consumers/oauth2/v2.0/devicecode" => MockResponse::json(
+            200,
+            json!({
+                "device_code": "device-code",
+                "user_code": "ABCD-EFGH",
+                "verification_uri": "https://microsoft.com/devicelogin",
+                "expires_in": 900,
+                "interval": 1
+            }),
+        ),
+        "/consumers/oauth2/v2.0/token" => MockResponse::json(
+            200,
+            json!({
+                "access_token": "microsoft-access-token",
+                "refresh_token": "microsoft-refresh-token",
+                "expires_in": 3600,
+                "token_type": "Bearer"
+            }),
+        ),
+        "/user/authenticate" => MockResponse::json(
+            200,
+            json!({ "Token": "user-token", "DisplayClaims": { "xui": [{ "uhs": "user-hash" }] } }),
+        ),
+        "/xsts/authorize" => MockResponse::json(
+            200,
+            json!({
+                "Token": "xsts-token",
+                "DisplayClaims": { "xui": [{ "uhs": "user-hash", "xid": "2535" }] }
+            }),
+        ),
+        "/authentication/login_with_xbox" => MockResponse::json(
+            200,
+            json!({
+                "access_token": "minecraft-access-token",
+                "expires_in": 86400,
+                "token_type": "Bearer"
+            }),
+        ),
+        "/minecraft/profile" => MockResponse::json(
+            200,
+            json!({ "id": "069a79f444e94726a5befca90e38aaf5", "name": "Notch" }),
+        ),
+        _ => MockResponse::new(404, ""),
+    })
+    .await
+}
+
+/// A fresh, empty folder under the system temp directory.
+pub fn temp_dir(name: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "minecraft-launcher-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&path);
+    std::fs::create_dir_all(&path).unwrap();
+
+    path
+}
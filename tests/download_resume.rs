@@ -0,0 +1,126 @@
+mod common;
+
+use common::{temp_dir, MockRequest, MockResponse, MockServer};
+use minecraft_launcher::files::{compute_sha1, download_file};
+
+const PART_SIZE: usize = 40_000;
+
+fn file_content() -> Vec<u8> {
+    (0..100_000u32).map(|i| (i * 31 % 251) as u8).collect()
+}
+
+/// Serves `file_content` at `/file`, honouring `Range: bytes=N-`.
+fn serve_ranges(request: &MockRequest) -> MockResponse {
+    let content = file_content();
+
+    let range_start = request
+        .header("range")
+        .and_then(|range| range.strip_prefix("bytes="))
+        .and_then(|range| range.strip_suffix('-'))
+        .and_then(|start| start.parse::<usize>().ok());
+
+    match range_start {
+        Some(start) if start >= content.len() => MockResponse::new(416, ""),
+        Some(start) => MockResponse::new(206, content[start..].to_vec()).with_header(
+            "Content-Range",
+            &format!("bytes {}-{}/{}", start, content.len() - 1, content.len()),
+        ),
+        None => MockResponse::new(200, content),
+    }
+}
+
+fn range_headers(server: &MockServer) -> Vec<Option<String>> {
+    server
+        .requests()
+        .iter()
+        .map(|request| request.header("range").map(str::to_string))
+        .collect()
+}
+
+#[tokio::test]
+async fn resumes_a_partial_download() {
+    let server = MockServer::start(serve_ranges).await;
+    let dir = temp_dir("resume");
+    let path = dir.join("file.bin").to_string_lossy().to_string();
+    let content = file_content();
+    std::fs::write(format!("{}.part", path), &content[..PART_SIZE]).unwrap();
+
+    download_file(
+        path.clone(),
+        server.url("/file"),
+        Some(&compute_sha1(&content)),
+        Some(content.len() as u64),
+        &|_| {},
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(std::fs::read(&path).unwrap(), content);
+    assert!(!std::path::Path::new(&format!("{}.part", path)).exists());
+    assert_eq!(
+        range_headers(&server),
+        vec![Some(format!("bytes={}-", PART_SIZE))]
+    );
+}
+
+#[tokio::test]
+async fn restarts_when_a_stale_part_does_not_match() {
+    let server = MockServer::start(serve_ranges).await;
+    let dir = temp_dir("stale-part");
+    let path = dir.join("file.bin").to_string_lossy().to_string();
+    let content = file_content();
+    std::fs::write(format!("{}.part", path), vec![0xAA; PART_SIZE]).unwrap();
+
+    download_file(
+        path.clone(),
+        server.url("/file"),
+        Some(&compute_sha1(&content)),
+        Some(content.len() as u64),
+        &|_| {},
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(std::fs::read(&path).unwrap(), content);
+    assert_eq!(
+        range_headers(&server),
+        vec![Some(format!("bytes={}-", PART_SIZE)), None]
+    );
+}
+
+#[tokio::test]
+async fn does_not_resume_unverifiable_files() {
+    let server = MockServer::start(serve_ranges).await;
+    let dir = temp_dir("unverifiable");
+    let path = dir.join("manifest.json").to_string_lossy().to_string();
+    std::fs::write(format!("{}.part", path), vec![0xAA; PART_SIZE]).unwrap();
+
+    download_file(path.clone(), server.url("/file"), None, None, &|_| {})
+        .await
+        .unwrap();
+
+    assert_eq!(std::fs::read(&path).unwrap(), file_content());
+    assert_eq!(range_headers(&server), vec![None]);
+}
+
+#[tokio::test]
+async fn restarts_when_the_server_ignores_ranges() {
+    let server = MockServer::start(|_| MockResponse::new(200, file_content())).await;
+    let dir = temp_dir("ignored-range");
+    let path = dir.join("file.bin").to_string_lossy().to_string();
+    let content = file_content();
+    std::fs::write(format!("{}.part", path), &content[..PART_SIZE]).unwrap();
+
+    download_file(
+        path.clone(),
+        server.url("/file"),
+        Some(&compute_sha1(&content)),
+        Some(content.len() as u64),
+        &|_| {},
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(std::fs::read(&path).unwrap(), content);
+    assert_eq!(server.requests().len(), 1);
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Mutex,
+};
+
+use common::{temp_dir, MockResponse, MockServer};
+use minecraft_launcher::{files::download_file, http_client::TransferEvent};
+
+#[tokio::test]
+async fn reports_retries_and_honours_retry_after() {
+    let attempts = AtomicUsize::new(0);
+    let server = MockServer::start(move |_| {
+        if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+            MockResponse::new(503, "").with_header("Retry-After", "0")
+        } else {
+            MockResponse::new(200, "content")
+        }
+    })
+    .await;
+    let dir = temp_dir("retry");
+    let path = dir.join("file.txt").to_string_lossy().to_string();
+    let retries = Mutex::new(Vec::new());
+
+    download_file(path.clone(), server.url("/file"), None, None, &|event| {
+        if let TransferEvent::Retrying { retry, delay, .. } = event {
+            retries.lock().unwrap().push((retry, delay));
+        }
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "content");
+    assert_eq!(
+        retries.into_inner().unwrap(),
+        vec![(1, std::time::Duration::ZERO)]
+    );
+    assert_eq!(server.requests().len(), 2);
+}
@@ -0,0 +1,188 @@
+mod common;
+
+use common::temp_dir;
+use minecraft_launcher::{
+    instances::InstanceStore,
+    model::mojang::{LauncherProfiles, MojangVersionManifest, MojangVersionManifestLatest},
+    LauncherPaths,
+};
+
+fn version_manifest() -> MojangVersionManifest {
+    MojangVersionManifest {
+        latest: MojangVersionManifestLatest {
+            release: "1.21.1".to_string(),
+            snapshot: "24w40a".to_string(),
+        },
+        versions: Vec::new(),
+    }
+}
+
+const FABRIC_VERSION: &str = "fabric-loader-0.16.5-1.21.1";
+const FABRIC_LOADER_JAR: &str = "net/fabricmc/fabric-loader/0.16.5/fabric-loader-0.16.5.jar";
+const UNUSED_LIBRARY_JAR: &str = "org/example/unused/1.0/unused-1.0.jar";
+
+fn write_file(path: std::path::PathBuf, content: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+/// A Fabric version inheriting from 1.21.1, plus a version and a library
+/// no profile uses.
+fn write_versions(minecraft_dir: &std::path::Path) {
+    let fabric_profile = serde_json::json!({
+        "id": FABRIC_VERSION,
+        "inheritsFrom": "1.21.1",
+        "mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient",
+        "libraries": [
+            { "name": "net.fabricmc:fabric-loader:0.16.5", "url": "https://maven.fabricmc.net/" }
+        ]
+    });
+    write_file(
+        minecraft_dir.join(format!("versions/{0}/{0}.json", FABRIC_VERSION)),
+        &fabric_profile.to_string(),
+    );
+    write_file(
+        minecraft_dir.join("versions/1.21.1/1.21.1.json"),
+        r#"{ "id": "1.21.1" }"#,
+    );
+    write_file(minecraft_dir.join("versions/1.21.1/1.21.1.jar"), "client");
+    write_file(
+        minecraft_dir.join("versions/1.8.9/1.8.9.json"),
+        r#"{ "id": "1.8.9" }"#,
+    );
+    write_file(
+        minecraft_dir.join("libraries").join(FABRIC_LOADER_JAR),
+        "loader",
+    );
+    write_file(
+        minecraft_dir.join("libraries").join(UNUSED_LIBRARY_JAR),
+        "unused",
+    );
+}
+
+fn write_launcher_profiles(minecraft_dir: &std::path::Path) {
+    let launcher_profiles = serde_json::json!({
+        "profiles": {
+            "3f1c5d8e": {
+                "name": "Survival",
+                "type": "custom",
+                "lastVersionId": "1.20.1",
+                "javaArgs": "-Xmx4G"
+            },
+            "b7e2a9c4": {
+                "name": "",
+                "type": "latest-release"
+            },
+            "0d9f7a21": {
+                "name": "Broken",
+                "type": "custom"
+            },
+            "e5a0c3b6": {
+                "name": "Fabric",
+                "type": "custom",
+                "lastVersionId": FABRIC_VERSION
+            }
+        },
+        "settings": { "enableSnapshots": true },
+        "version": 3
+    });
+    std::fs::write(
+        minecraft_dir.join("launcher_profiles.json"),
+        serde_json::to_string_pretty(&launcher_profiles).unwrap(),
+    )
+    .unwrap();
+}
+
+#[tokio::test]
+async fn imports_profiles_once_and_exports_them_back() {
+    let dir = temp_dir("launcher-profiles");
+    let minecraft_dir = dir.join(".minecraft");
+    std::fs::create_dir_all(&minecraft_dir).unwrap();
+    write_launcher_profiles(&minecraft_dir);
+    write_versions(&minecraft_dir);
+    let minecraft_dir = minecraft_dir.to_string_lossy().to_string();
+
+    let launcher_dir = dir.join("launcher");
+    let launcher_paths = LauncherPaths::new(launcher_dir.to_string_lossy().to_string());
+    let mut store = InstanceStore::load(launcher_paths.clone()).await.unwrap();
+
+    let mut imported = store
+        .import_launcher_profiles(&minecraft_dir, &version_manifest())
+        .await
+        .unwrap();
+    imported.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let imported: Vec<_> = imported
+        .iter()
+        .map(|instance| {
+            (
+                instance.name.as_str(),
+                instance.version_id.as_str(),
+                instance.launcher_profile_key.as_deref(),
+                instance.installed_version_id.as_deref(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        imported,
+        vec![
+            ("1.21.1", "1.21.1", Some("b7e2a9c4"), None),
+            ("Fabric", "1.21.1", Some("e5a0c3b6"), Some(FABRIC_VERSION)),
+            ("Survival", "1.20.1", Some("3f1c5d8e"), None),
+        ]
+    );
+
+    // Only the versions and libraries the profiles launch are copied.
+    assert!(launcher_dir
+        .join(format!("versions/{0}/{0}.json", FABRIC_VERSION))
+        .is_file());
+    assert!(launcher_dir.join("versions/1.21.1/1.21.1.jar").is_file());
+    assert!(launcher_dir
+        .join("libraries")
+        .join(FABRIC_LOADER_JAR)
+        .is_file());
+    assert!(!launcher_dir.join("versions/1.8.9").exists());
+    assert!(!launcher_dir
+        .join("libraries")
+        .join(UNUSED_LIBRARY_JAR)
+        .exists());
+
+    let mut store = InstanceStore::load(launcher_paths).await.unwrap();
+    let imported_again = store
+        .import_launcher_profiles(&minecraft_dir, &version_manifest())
+        .await
+        .unwrap();
+    assert!(imported_again.is_empty());
+    assert_eq!(store.instances().len(), 3);
+
+    store
+        .export_launcher_profiles(&minecraft_dir)
+        .await
+        .unwrap();
+
+    let exported: LauncherProfiles = serde_json::from_str(
+        &std::fs::read_to_string(format!("{}/launcher_profiles.json", minecraft_dir)).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        exported.profiles.keys().collect::<Vec<_>>(),
+        vec!["0d9f7a21", "3f1c5d8e", "b7e2a9c4", "e5a0c3b6"]
+    );
+    assert_eq!(
+        exported.profiles["3f1c5d8e"].java_args.as_deref(),
+        Some("-Xmx4096M")
+    );
+    assert_eq!(exported.other["version"], 3);
+
+    // The latest release profile keeps following it instead of being pinned.
+    let latest_release = &exported.profiles["b7e2a9c4"];
+    assert_eq!(latest_release.r#type.as_deref(), Some("latest-release"));
+    assert_eq!(
+        latest_release.last_version_id.as_deref(),
+        Some("latest-release")
+    );
+    assert_eq!(
+        exported.profiles["3f1c5d8e"].last_version_id.as_deref(),
+        Some("1.20.1")
+    );
+}
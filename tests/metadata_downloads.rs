@@ -0,0 +1,78 @@
+mod common;
+
+use common::{temp_dir, MockResponse, MockServer};
+use minecraft_launcher::files::{compute_sha1, load_file_or_download_serialized};
+use serde_json::json;
+
+fn version_content() -> Vec<u8> {
+    json!({ "id": "1.20.1", "mainClass": "net.minecraft.client.main.Main" })
+        .to_string()
+        .into_bytes()
+}
+
+#[tokio::test]
+async fn replaces_a_stale_version_json() {
+    let server = MockServer::start(|_| MockResponse::new(200, version_content())).await;
+    let dir = temp_dir("stale-version");
+    let path = dir.join("1.20.1.json").to_string_lossy().to_string();
+    std::fs::write(&path, json!({ "id": "1.20.1" }).to_string()).unwrap();
+
+    let version: serde_json::Value = load_file_or_download_serialized(
+        path.clone(),
+        server.url("/1.20.1.json"),
+        Some(&compute_sha1(&version_content())),
+        None,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(version["mainClass"], "net.minecraft.client.main.Main");
+    assert_eq!(std::fs::read(&path).unwrap(), version_content());
+}
+
+#[tokio::test]
+async fn keeps_a_valid_asset_index_without_downloading() {
+    let server = MockServer::start(|_| MockResponse::new(500, "")).await;
+    let dir = temp_dir("valid-asset-index");
+    let path = dir.join("5.json").to_string_lossy().to_string();
+    let content = json!({ "objects": {} }).to_string().into_bytes();
+    std::fs::write(&path, &content).unwrap();
+
+    let _: serde_json::Value = load_file_or_download_serialized(
+        path,
+        server.url("/5.json"),
+        Some(&compute_sha1(&content)),
+        Some(content.len() as u64),
+    )
+    .await
+    .unwrap();
+
+    assert!(server.requests().is_empty());
+}
+
+#[tokio::test]
+async fn fails_when_the_downloaded_asset_index_does_not_match() {
+    let server = MockServer::start(|_| MockResponse::new(200, version_content())).await;
+    let dir = temp_dir("mismatched-asset-index");
+    let path = dir.join("5.json").to_string_lossy().to_string();
+    let stale_content = json!({ "objects": {} }).to_string().into_bytes();
+    std::fs::write(&path, &stale_content).unwrap();
+    let expected_sha1 = compute_sha1(b"the published asset index");
+
+    let result: minecraft_launcher::Result<serde_json::Value> = load_file_or_download_serialized(
+        path.clone(),
+        server.url("/5.json"),
+        Some(&expected_sha1),
+        Some(stale_content.len() as u64),
+    )
+    .await;
+
+    match result {
+        Err(minecraft_launcher::Error::FileHashMismatch { expected, .. }) => {
+            assert_eq!(expected, expected_sha1)
+        }
+        other => panic!("expected a hash mismatch, got {:?}", other),
+    }
+    assert!(!server.requests().is_empty());
+    assert_eq!(std::fs::read(&path).unwrap(), stale_content);
+}
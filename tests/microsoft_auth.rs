@@ -0,0 +1,83 @@
+mod common;
+
+use common::start_auth_server;
+use minecraft_launcher::auth::{AuthEndpoints, MicrosoftAuthenticator};
+use serde_json::json;
+
+#[tokio::test]
+async fn device_code_flow_creates_a_session() {
+    let server = start_auth_server().await;
+    let authenticator = MicrosoftAuthenticator::new(
+        "client-id".to_string(),
+        AuthEndpoints::with_base_url(&server.base_url),
+    );
+
+    let device_code = authenticator.request_device_code().await.unwrap();
+    assert_eq!(device_code.user_code, "ABCD-EFGH");
+
+    let session = authenticator
+        .login_with_device_code(&device_code)
+        .await
+        .unwrap();
+    assert_eq!(session.username, "Notch");
+    assert_eq!(session.uuid, "069a79f444e94726a5befca90e38aaf5");
+    assert_eq!(session.access_token, "minecraft-access-token");
+    assert_eq!(session.xuid.as_deref(), Some("2535"));
+    assert_eq!(
+        session.refresh_token.as_deref(),
+        Some("microsoft-refresh-token")
+    );
+
+    let requests = server.requests();
+    let find = |path: &str| {
+        requests
+            .iter()
+            .find(|request| request.path == path)
+            .unwrap()
+            .clone()
+    };
+
+    let user_properties = find("/user/authenticate").json()["Properties"].clone();
+    assert_eq!(user_properties["RpsTicket"], "d=microsoft-access-token");
+    assert!(user_properties.get("SandboxId").is_none());
+    assert!(user_properties.get("UserTokens").is_none());
+
+    let xsts_properties = find("/xsts/authorize").json()["Properties"].clone();
+    assert_eq!(xsts_properties["UserTokens"], json!(["user-token"]));
+    assert!(xsts_properties.get("AuthMethod").is_none());
+    assert!(xsts_properties.get("SiteName").is_none());
+    assert!(xsts_properties.get("RpsTicket").is_none());
+
+    assert_eq!(
+        find("/authentication/login_with_xbox").json()["identityToken"],
+        "XBL3.0 x=user-hash;xsts-token"
+    );
+    assert_eq!(
+        find("/minecraft/profile").header("authorization"),
+        Some("Bearer minecraft-access-token")
+    );
+}
+
+#[tokio::test]
+async fn refresh_keeps_the_refresh_token() {
+    let server = start_auth_server().await;
+    let authenticator = MicrosoftAuthenticator::new(
+        "client-id".to_string(),
+        AuthEndpoints::with_base_url(&server.base_url),
+    );
+
+    let session = authenticator
+        .refresh_session("old-refresh-token")
+        .await
+        .unwrap();
+    assert_eq!(session.access_token, "minecraft-access-token");
+
+    let token_request = server
+        .requests()
+        .into_iter()
+        .find(|request| request.path == "/consumers/oauth2/v2.0/token")
+        .unwrap();
+    let form = String::from_utf8(token_request.body).unwrap();
+    assert!(form.contains("grant_type=refresh_token"));
+    assert!(form.contains("refresh_token=old-refresh-token"));
+}
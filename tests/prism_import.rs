@@ -0,0 +1,44 @@
+mod common;
+
+use std::path::Path;
+
+use common::temp_dir;
+use minecraft_launcher::{
+    instances::{InstanceStore, ModLoaderKind},
+    LauncherPaths,
+};
+
+#[tokio::test]
+async fn imports_an_instance_folder() {
+    let launcher_paths = LauncherPaths::new(temp_dir("prism-import").to_string_lossy().to_string());
+    let mut store = InstanceStore::load(launcher_paths.clone()).await.unwrap();
+
+    let instance = store
+        .import_prism_instance(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/prism/forge-instance"
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(instance.name, "Create: Above & Beyond");
+    assert_eq!(instance.id, "create-above-beyond");
+    assert_eq!(
+        instance
+            .mod_loader
+            .as_ref()
+            .map(|mod_loader| mod_loader.kind),
+        Some(ModLoaderKind::Forge)
+    );
+    assert_eq!(instance.settings.jvm.max_memory_mb, Some(6144));
+
+    let game_dir = Path::new(&instance.game_directory_path(&launcher_paths)).to_path_buf();
+    assert_eq!(
+        std::fs::read_to_string(game_dir.join("options.txt")).unwrap(),
+        "renderDistance:12\n"
+    );
+    assert!(game_dir.join("saves/World/level.dat").is_file());
+
+    let reloaded = InstanceStore::load(launcher_paths).await.unwrap();
+    assert_eq!(reloaded.instances().len(), 1);
+}